@@ -0,0 +1,552 @@
+use super::*;
+
+/// What an analyzer sees of one retrieved page. `row` carries the metrics
+/// already extracted (title, counts, audited headers, ...); `document` is
+/// set for HTML responses and `rendered_html` when a browser rendered them.
+/// `url` is the normalized URL the row is exported under; `requested_url` is
+/// the form the page was requested or linked as.
+pub struct PageContext<'a> {
+    pub url: &'a str,
+    pub requested_url: &'a str,
+    pub status: u16,
+    pub headers: &'a [(String, String)],
+    pub document: Option<&'a Html>,
+    pub rendered_html: Option<&'a str>,
+    pub row: &'a CrawlRow,
+}
+
+impl PageContext<'_> {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header_name, _)| header_name.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn is_success(&self) -> bool {
+        (200..=299).contains(&self.status)
+    }
+
+    /// A 2xx HTML page, the only kind the content checks apply to.
+    pub fn is_html_success(&self) -> bool {
+        self.is_success() && self.document.is_some()
+    }
+}
+
+/// Issues and extra export fields collected from all analyzers for a page.
+#[derive(Debug, Default)]
+pub struct AnalyzerOutput {
+    pub issues: Vec<SeoIssue>,
+    pub fields: BTreeMap<String, String>,
+}
+
+/// A page check. Built-in checks are analyzers too and can be disabled by
+/// name; libraries add their own through [`AnalyzerRegistry::register`].
+pub trait Analyzer: Send + Sync {
+    fn name(&self) -> &str;
+    fn analyze(&self, page: &PageContext<'_>, out: &mut AnalyzerOutput);
+}
+
+struct StatusAnalyzer;
+
+impl Analyzer for StatusAnalyzer {
+    fn name(&self) -> &str {
+        "status"
+    }
+
+    fn analyze(&self, page: &PageContext<'_>, out: &mut AnalyzerOutput) {
+        if (400..=499).contains(&page.status) {
+            out.issues.push(SeoIssue::Http4xx);
+        }
+        if (500..=599).contains(&page.status) {
+            out.issues.push(SeoIssue::Http5xx);
+        }
+    }
+}
+
+struct UrlHygieneAnalyzer;
+
+impl Analyzer for UrlHygieneAnalyzer {
+    fn name(&self) -> &str {
+        "url"
+    }
+
+    fn analyze(&self, page: &PageContext<'_>, out: &mut AnalyzerOutput) {
+        let Some(doc) = page.document.filter(|_| page.is_html_success()) else {
+            return;
+        };
+        // Normalization would hide the very problems these checks look for.
+        let canonical = canonical_href(doc, page.requested_url).unwrap_or_default();
+        out.issues
+            .extend(collect_url_issues(page.requested_url, &canonical));
+    }
+}
+
+struct HttpsAnalyzer;
+
+impl Analyzer for HttpsAnalyzer {
+    fn name(&self) -> &str {
+        "https"
+    }
+
+    fn analyze(&self, page: &PageContext<'_>, out: &mut AnalyzerOutput) {
+        // Assets inherit the transport of the page that loads them, so only
+        // HTML documents are audited.
+        let Some(doc) = page.document.filter(|_| page.is_html_success()) else {
+            return;
+        };
+        match url_scheme(page.url).as_deref() {
+            Some("http") => out.issues.push(SeoIssue::HttpWithoutHttpsRedirect),
+            Some("https") if page.row.strict_transport_security.trim().is_empty() => {
+                out.issues.push(SeoIssue::MissingHsts);
+            }
+            _ => {}
+        }
+        if page.row.mixed_content_count > 0 {
+            out.issues.push(SeoIssue::MixedContent);
+        }
+        if page.row.insecure_link_count > 0 {
+            out.issues.push(SeoIssue::InsecureInternalLinks);
+        }
+        if canonical_protocol_mismatch(page.url, &page.row.canonical) {
+            out.issues.push(SeoIssue::CanonicalProtocolMismatch);
+        }
+        if count_hreflang_protocol_mismatches(doc, page.url) > 0 {
+            out.issues.push(SeoIssue::HreflangProtocolMismatch);
+        }
+    }
+}
+
+struct ContentAnalyzer;
+
+impl Analyzer for ContentAnalyzer {
+    fn name(&self) -> &str {
+        "content"
+    }
+
+    fn analyze(&self, page: &PageContext<'_>, out: &mut AnalyzerOutput) {
+        if !page.is_html_success() {
+            return;
+        }
+        let row = page.row;
+        // Indexability of a 2xx page only turns negative through noindex.
+        if row.indexability == "Non-Indexable" {
+            out.issues.push(SeoIssue::Noindex);
+        }
+        if row.title_length == 0 {
+            out.issues.push(SeoIssue::MissingTitle);
+        } else if row.title_length < 15 {
+            out.issues.push(SeoIssue::TitleTooShort);
+        } else if row.title_length > 60 {
+            out.issues.push(SeoIssue::TitleTooLong);
+        }
+
+        if row.meta_length == 0 {
+            out.issues.push(SeoIssue::MissingMetaDescription);
+        } else if row.meta_length < 70 {
+            out.issues.push(SeoIssue::MetaDescriptionTooShort);
+        } else if row.meta_length > 160 {
+            out.issues.push(SeoIssue::MetaDescriptionTooLong);
+        }
+
+        if row.h1_count == 0 {
+            out.issues.push(SeoIssue::MissingH1);
+        } else if row.h1_count > 1 {
+            out.issues.push(SeoIssue::MultipleH1);
+        }
+
+        if row.word_count < 120 {
+            out.issues.push(SeoIssue::LowWordCount);
+        }
+        if row.image_missing_alt_count > 0 {
+            out.issues.push(SeoIssue::ImagesMissingAlt);
+        }
+        if row.external_link_count > 60 {
+            out.issues.push(SeoIssue::TooManyExternalLinks);
+        }
+    }
+}
+
+struct CanonicalAnalyzer;
+
+impl Analyzer for CanonicalAnalyzer {
+    fn name(&self) -> &str {
+        "canonical"
+    }
+
+    fn analyze(&self, page: &PageContext<'_>, out: &mut AnalyzerOutput) {
+        if !page.is_html_success() {
+            return;
+        }
+        if page.row.canonical.trim().is_empty() {
+            out.issues.push(SeoIssue::MissingCanonical);
+        }
+        if paginated_canonical_to_first(page.url, &page.row.canonical) {
+            out.issues.push(SeoIssue::PaginatedCanonicalToFirst);
+        }
+    }
+}
+
+struct HeaderAnalyzer;
+
+impl Analyzer for HeaderAnalyzer {
+    fn name(&self) -> &str {
+        "headers"
+    }
+
+    fn analyze(&self, page: &PageContext<'_>, out: &mut AnalyzerOutput) {
+        out.issues.extend(collect_header_issues(page.row));
+    }
+}
+
+/// Analyzers in run order. [`AnalyzerRegistry::global`] holds the set library
+/// code registers into; each crawl runs its own copy, taken when it starts,
+/// with that crawl's rules and disabled analyzers applied.
+pub struct AnalyzerRegistry {
+    analyzers: std::sync::RwLock<Vec<Arc<dyn Analyzer>>>,
+    disabled: std::sync::RwLock<HashSet<String>>,
+}
+
+static ANALYZER_REGISTRY: std::sync::OnceLock<AnalyzerRegistry> = std::sync::OnceLock::new();
+
+impl AnalyzerRegistry {
+    pub fn global() -> &'static AnalyzerRegistry {
+        ANALYZER_REGISTRY.get_or_init(AnalyzerRegistry::default)
+    }
+
+    /// A copy of the global registry for one crawl, with its `--rules` and
+    /// `--disable-analyzer` options applied.
+    pub(crate) fn for_crawl(
+        cli: &Cli,
+        extra: &[Arc<dyn Analyzer>],
+    ) -> Result<AnalyzerRegistry, String> {
+        let global = AnalyzerRegistry::global();
+        let registry = AnalyzerRegistry {
+            analyzers: std::sync::RwLock::new(
+                global
+                    .analyzers
+                    .read()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .clone(),
+            ),
+            disabled: std::sync::RwLock::new(
+                global
+                    .disabled
+                    .read()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .clone(),
+            ),
+        };
+        for analyzer in extra {
+            registry.register_arc(analyzer.clone());
+        }
+        registry.apply_cli(cli)?;
+        Ok(registry)
+    }
+
+    /// Adds an analyzer after the existing ones, or replaces the one with
+    /// the same name in place.
+    pub fn register(&self, analyzer: impl Analyzer + 'static) {
+        self.register_arc(Arc::new(analyzer));
+    }
+
+    fn register_arc(&self, analyzer: Arc<dyn Analyzer>) {
+        let mut analyzers = self.analyzers.write().unwrap_or_else(|e| e.into_inner());
+        match analyzers
+            .iter()
+            .position(|existing| existing.name() == analyzer.name())
+        {
+            Some(index) => analyzers[index] = analyzer,
+            None => analyzers.push(analyzer),
+        }
+    }
+
+    /// Returns false when no analyzer has that name.
+    pub fn set_enabled(&self, name: &str, enabled: bool) -> bool {
+        let known = self
+            .analyzers
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .any(|analyzer| analyzer.name() == name);
+        if known {
+            let mut disabled = self.disabled.write().unwrap_or_else(|e| e.into_inner());
+            if enabled {
+                disabled.remove(name);
+            } else {
+                disabled.insert(name.to_string());
+            }
+        }
+        known
+    }
+
+    /// Analyzer names in run order, with whether each is enabled.
+    pub fn names(&self) -> Vec<(String, bool)> {
+        let disabled = self.disabled.read().unwrap_or_else(|e| e.into_inner());
+        self.analyzers
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .map(|analyzer| {
+                let name = analyzer.name().to_string();
+                let enabled = !disabled.contains(&name);
+                (name, enabled)
+            })
+            .collect()
+    }
+
+    fn apply_cli(&self, cli: &Cli) -> Result<(), String> {
+        if let Some(path) = cli.rules.as_deref() {
+            self.register(RuleAnalyzer::from_file(path)?);
+        }
+        for name in &cli.disable_analyzers {
+            let name = name.trim();
+            if !name.is_empty() && !self.set_enabled(name, false) {
+                let available = self
+                    .names()
+                    .into_iter()
+                    .map(|(name, _)| name)
+                    .collect::<Vec<_>>();
+                return Err(format!(
+                    "unknown analyzer '{name}' (available: {})",
+                    available.join(", ")
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn analyze(&self, page: &PageContext<'_>) -> AnalyzerOutput {
+        // Run outside the lock so analyzers may use the registry themselves.
+        let analyzers = self
+            .analyzers
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone();
+        let disabled = self
+            .disabled
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone();
+        let mut out = AnalyzerOutput::default();
+        for analyzer in analyzers
+            .iter()
+            .filter(|analyzer| !disabled.contains(analyzer.name()))
+        {
+            analyzer.analyze(page, &mut out);
+        }
+        let mut seen = HashSet::new();
+        out.issues.retain(|issue| seen.insert(issue.clone()));
+        out
+    }
+}
+
+impl Default for AnalyzerRegistry {
+    /// The built-in checks.
+    fn default() -> Self {
+        AnalyzerRegistry {
+            analyzers: std::sync::RwLock::new(vec![
+                Arc::new(StatusAnalyzer),
+                Arc::new(UrlHygieneAnalyzer),
+                Arc::new(HttpsAnalyzer),
+                Arc::new(ContentAnalyzer),
+                Arc::new(CanonicalAnalyzer),
+                Arc::new(HeaderAnalyzer),
+            ]),
+            disabled: Default::default(),
+        }
+    }
+}
+
+/// Runs the enabled analyzers over a retrieved page and stores their
+/// issues, extra fields and the resulting score on the row.
+pub(crate) fn analyze_row(
+    analyzers: &AnalyzerRegistry,
+    row: &mut CrawlRow,
+    requested_url: &str,
+    document: Option<&Html>,
+    rendered_html: Option<&str>,
+    headers: &[(String, String)],
+) {
+    if row.retrieval_status != "retrieved" {
+        row.issues = vec![SeoIssue::NotRetrieved];
+        row.seo_score = compute_seo_score(&row.issues);
+        return;
+    }
+    let output = analyzers.analyze(&PageContext {
+        url: &row.url,
+        requested_url,
+        status: row.status,
+        headers,
+        document,
+        rendered_html,
+        row,
+    });
+    row.issues = output.issues;
+    row.extra_fields = output.fields;
+    row.seo_score = compute_seo_score(&row.issues);
+}
+
+/// Response headers a row still knows about, for pages whose original
+/// response is gone (rendered snapshots).
+pub(crate) fn row_headers(row: &CrawlRow) -> Vec<(String, String)> {
+    std::iter::once("last-modified")
+        .chain(AUDITED_HEADERS)
+        .filter_map(|name| {
+            let value = if name == "last-modified" {
+                row.last_modified.as_str()
+            } else {
+                row.header(name)
+            };
+            (!value.trim().is_empty()).then(|| (name.to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Appends its name to the `order` field.
+    struct Recorder(&'static str);
+
+    impl Analyzer for Recorder {
+        fn name(&self) -> &str {
+            self.0
+        }
+
+        fn analyze(&self, _page: &PageContext<'_>, out: &mut AnalyzerOutput) {
+            out.fields
+                .entry("order".to_string())
+                .or_default()
+                .push_str(self.0);
+        }
+    }
+
+    fn retrieved_row(url: &str) -> CrawlRow {
+        let mut row = unretrieved_row(url.to_string(), String::new());
+        row.status = 200;
+        row.retrieval_status = "retrieved".to_string();
+        row
+    }
+
+    fn run(registry: &AnalyzerRegistry, row: &CrawlRow, document: Option<&Html>) -> AnalyzerOutput {
+        registry.analyze(&PageContext {
+            url: &row.url,
+            requested_url: &row.url,
+            status: row.status,
+            headers: &[],
+            document,
+            rendered_html: None,
+            row,
+        })
+    }
+
+    #[test]
+    fn registry_runs_in_order_and_replaces_by_name() {
+        let registry = AnalyzerRegistry {
+            analyzers: Default::default(),
+            disabled: Default::default(),
+        };
+        registry.register(Recorder("a"));
+        registry.register(Recorder("b"));
+        registry.register(Recorder("c"));
+        registry.register(Recorder("b"));
+        let names = registry.names();
+        assert_eq!(
+            names
+                .iter()
+                .map(|(name, _)| name.as_str())
+                .collect::<Vec<_>>(),
+            ["a", "b", "c"]
+        );
+        let out = run(&registry, &retrieved_row("https://example.com/"), None);
+        assert_eq!(out.fields["order"], "abc");
+
+        assert!(registry.set_enabled("b", false));
+        assert!(!registry.set_enabled("missing", false));
+        assert!(registry.names().contains(&("b".to_string(), false)));
+        let out = run(&registry, &retrieved_row("https://example.com/"), None);
+        assert_eq!(out.fields["order"], "ac");
+    }
+
+    #[test]
+    fn built_in_analyzers_can_be_disabled() {
+        let names = AnalyzerRegistry::default()
+            .names()
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            ["status", "url", "https", "content", "canonical", "headers"]
+        );
+
+        let doc = Html::parse_document("<html><body><p>Hi</p></body></html>");
+        let row = retrieved_row("https://example.com/");
+        let registry = AnalyzerRegistry::default();
+        assert!(
+            run(&registry, &row, Some(&doc))
+                .issues
+                .contains(&SeoIssue::MissingTitle)
+        );
+        registry.set_enabled("content", false);
+        assert!(
+            !run(&registry, &row, Some(&doc))
+                .issues
+                .contains(&SeoIssue::MissingTitle)
+        );
+    }
+
+    #[test]
+    fn https_checks_skip_assets() {
+        let registry = AnalyzerRegistry::default();
+        let asset = retrieved_row("https://example.com/app.js");
+        assert!(
+            !run(&registry, &asset, None)
+                .issues
+                .contains(&SeoIssue::MissingHsts)
+        );
+        let asset = retrieved_row("http://example.com/logo.png");
+        let issues = run(&registry, &asset, None).issues;
+        assert!(!issues.contains(&SeoIssue::HttpWithoutHttpsRedirect));
+
+        let doc = Html::parse_document("<html><body></body></html>");
+        let page = retrieved_row("https://example.com/");
+        assert!(
+            run(&registry, &page, Some(&doc))
+                .issues
+                .contains(&SeoIssue::MissingHsts)
+        );
+    }
+
+    #[test]
+    fn each_crawl_gets_its_own_copy_of_the_registry() {
+        let cli = Cli::try_parse_from([
+            "gh0st",
+            "--disable-analyzer",
+            "content,headers",
+            "https://example.com",
+        ])
+        .unwrap();
+        let extra: Vec<Arc<dyn Analyzer>> = vec![Arc::new(Recorder("crawl_only"))];
+        let crawl = AnalyzerRegistry::for_crawl(&cli, &extra).unwrap();
+        let names = crawl.names();
+        assert_eq!(names.last().unwrap(), &("crawl_only".to_string(), true));
+        assert!(names.contains(&("content".to_string(), false)));
+        assert!(names.contains(&("headers".to_string(), false)));
+
+        let global = AnalyzerRegistry::global().names();
+        assert!(
+            global
+                .iter()
+                .all(|(name, enabled)| name != "crawl_only" && *enabled)
+        );
+
+        let cli =
+            Cli::try_parse_from(["gh0st", "--disable-analyzer", "nope", "https://example.com"])
+                .unwrap();
+        let err = AnalyzerRegistry::for_crawl(&cli, &[]).err().unwrap();
+        assert!(err.contains("unknown analyzer 'nope'"), "{err}");
+    }
+}
@@ -0,0 +1,373 @@
+use super::*;
+
+/// `Cli` fields (and their flags) for the `gh0st` command itself: other
+/// modes, exports, the headless run and its API.
+const COMMAND_LINE_ONLY: [(&str, &str); 10] = [
+    ("review_file", "--review"),
+    ("verify_redirects", "--verify-redirects"),
+    ("list", "--list"),
+    ("output", "--output"),
+    ("no_tui", "--no-tui"),
+    ("api", "--api"),
+    ("metrics", "--metrics"),
+    ("state_dir", "--state-dir"),
+    ("progress_format", "--progress-format"),
+    ("progress_file", "--progress-file"),
+];
+
+/// Configures a crawl with the same options as the command line and runs it
+/// in the background of the current Tokio runtime. Every option, including
+/// URL normalization, rules and disabled analyzers, applies to this crawl
+/// only.
+#[derive(Clone, Default)]
+pub struct CrawlBuilder {
+    urls: Vec<String>,
+    args: Vec<String>,
+    analyzers: Vec<Arc<dyn Analyzer>>,
+}
+
+impl std::fmt::Debug for CrawlBuilder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CrawlBuilder")
+            .field("urls", &self.urls)
+            .field("args", &self.args)
+            .field(
+                "analyzers",
+                &self
+                    .analyzers
+                    .iter()
+                    .map(|analyzer| analyzer.name())
+                    .collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+impl CrawlBuilder {
+    pub fn new(url: impl Into<String>) -> Self {
+        CrawlBuilder {
+            urls: vec![url.into()],
+            ..CrawlBuilder::default()
+        }
+    }
+
+    /// Adds another seed URL; its host joins the crawl scope.
+    pub fn seed(mut self, url: impl Into<String>) -> Self {
+        self.urls.push(url.into());
+        self
+    }
+
+    pub fn depth(self, depth: usize) -> Self {
+        self.arg("--depth").arg(depth.to_string())
+    }
+
+    pub fn max_pages(self, pages: usize) -> Self {
+        self.arg("--max-pages").arg(pages.to_string())
+    }
+
+    pub fn fetch_concurrency(self, concurrency: usize) -> Self {
+        self.arg("--fetch-concurrency").arg(concurrency.to_string())
+    }
+
+    pub fn rate_limit(self, requests_per_sec: f64) -> Self {
+        self.arg("--rate-limit").arg(requests_per_sec.to_string())
+    }
+
+    pub fn user_agent(self, user_agent: impl Into<String>) -> Self {
+        self.arg("--user-agent").arg(user_agent)
+    }
+
+    /// Extra request header in `Name: value` form.
+    pub fn header(self, header: impl Into<String>) -> Self {
+        self.arg("--header").arg(header)
+    }
+
+    pub fn respect_robots(self) -> Self {
+        self.arg("--respect-robots")
+    }
+
+    pub fn subdomains(self) -> Self {
+        self.arg("--subdomains")
+    }
+
+    /// Custom issue rules, see `--rules`.
+    pub fn rules(self, path: impl Into<String>) -> Self {
+        self.arg("--rules").arg(path)
+    }
+
+    /// Skips a registered analyzer in this crawl.
+    pub fn disable_analyzer(self, name: impl Into<String>) -> Self {
+        self.arg("--disable-analyzer").arg(name)
+    }
+
+    /// Runs an analyzer in this crawl only, after the globally registered
+    /// ones (or in place of the one with the same name).
+    pub fn analyzer(mut self, analyzer: impl Analyzer + 'static) -> Self {
+        self.analyzers.push(Arc::new(analyzer));
+        self
+    }
+
+    /// Any other command-line option, e.g. `.arg("--max-duration").arg("10m")`.
+    pub fn arg(mut self, arg: impl Into<String>) -> Self {
+        self.args.push(arg.into());
+        self
+    }
+
+    fn matches(&self) -> Result<ArgMatches, String> {
+        let args = std::iter::once("gh0st".to_string())
+            .chain(self.args.iter().cloned())
+            .chain(std::iter::once("--".to_string()))
+            .chain(self.urls.iter().cloned());
+        Cli::command()
+            .try_get_matches_from(args)
+            .map_err(|e| e.to_string())
+    }
+
+    fn cli(&self) -> Result<Cli, String> {
+        Cli::from_arg_matches(&self.matches()?).map_err(|e| e.to_string())
+    }
+
+    /// Starts the crawl. Must be called from within a Tokio runtime. Options
+    /// that only make sense for the `gh0st` command, such as `--output` or
+    /// `--api`, are rejected.
+    pub fn start(self) -> Result<CrawlHandle, String> {
+        let matches = self.matches()?;
+        if let Some((_, flag)) = COMMAND_LINE_ONLY
+            .iter()
+            .find(|(id, _)| matches.value_source(id) == Some(ValueSource::CommandLine))
+        {
+            return Err(format!("{flag} is not a crawl option"));
+        }
+        let cli = Cli::from_arg_matches(&matches).map_err(|e| e.to_string())?;
+        let analyzers = AnalyzerRegistry::for_crawl(&cli, &self.analyzers)?;
+        let (tx, events) = mpsc::unbounded_channel::<CrawlEvent>();
+        let (control, control_rx) = mpsc::unbounded_channel::<CrawlControl>();
+        let task = tokio::spawn(run_crawler(
+            cli,
+            analyzers,
+            None,
+            Arc::default(),
+            tx,
+            control_rx,
+        ));
+        Ok(CrawlHandle {
+            events,
+            control,
+            task,
+        })
+    }
+
+    /// Audits an HTML document fetched elsewhere the way this crawl would:
+    /// with its analyzers, rules, disabled analyzers and URL options. See
+    /// [`analyze_html`] for the arguments.
+    pub fn analyze_html(
+        &self,
+        url: &str,
+        status: u16,
+        headers: &[(&str, &str)],
+        html: &str,
+    ) -> Result<CrawlRow, String> {
+        let cli = self.cli()?;
+        let analyzers = AnalyzerRegistry::for_crawl(&cli, &self.analyzers)?;
+        let policy = UrlPolicy::from_cli(&cli);
+        Ok(analyze_document(
+            url, status, headers, html, &policy, &analyzers,
+        ))
+    }
+}
+
+/// A running crawl: its event stream and control channel. Dropping the
+/// handle stops the crawl.
+pub struct CrawlHandle {
+    events: UnboundedReceiver<CrawlEvent>,
+    control: UnboundedSender<CrawlControl>,
+    task: tokio::task::JoinHandle<()>,
+}
+
+impl CrawlHandle {
+    /// The next crawl event; `None` once the crawl has finished and every
+    /// event was delivered.
+    pub async fn next_event(&mut self) -> Option<CrawlEvent> {
+        self.events.recv().await
+    }
+
+    /// Sends a control command. Returns false when the crawl has ended.
+    pub fn control(&self, command: CrawlControl) -> bool {
+        self.control.send(command).is_ok()
+    }
+
+    /// Stops fetching until `resume`; pages already requested are still
+    /// delivered.
+    pub fn pause(&self) -> bool {
+        self.control(CrawlControl::Pause)
+    }
+
+    pub fn resume(&self) -> bool {
+        self.control(CrawlControl::Resume)
+    }
+
+    pub fn stop(&self) {
+        let _ = self.control.send(CrawlControl::Shutdown);
+    }
+
+    /// Waits for the crawl to finish and returns every exported row with its
+    /// outgoing links, in arrival order. Errors reported during the crawl are
+    /// skipped.
+    pub async fn collect_rows(mut self) -> Vec<(CrawlRow, Vec<String>)> {
+        let mut seen = HashSet::new();
+        let mut rows = Vec::new();
+        while let Some(event) = self.next_event().await {
+            let (row, links) = match event {
+                CrawlEvent::Page {
+                    row,
+                    discovered_links,
+                } => (*row, discovered_links),
+                CrawlEvent::Unretrieved { url, reason } => {
+                    (unretrieved_row(url, reason), Vec::new())
+                }
+                CrawlEvent::Finished => break,
+                _ => continue,
+            };
+            if seen.insert(row.url.clone()) {
+                rows.push((row, links));
+            }
+        }
+        rows
+    }
+}
+
+impl Drop for CrawlHandle {
+    fn drop(&mut self) {
+        let _ = self.control.send(CrawlControl::Shutdown);
+        self.task.abort();
+    }
+}
+
+/// Audits an HTML document fetched elsewhere and returns the row a crawl
+/// would export for it. `headers` feed the security and caching header
+/// checks; headers that are not given are reported as missing. Uses the
+/// global analyzers; [`CrawlBuilder::analyze_html`] applies a crawl's own.
+pub fn analyze_html(url: &str, status: u16, headers: &[(&str, &str)], html: &str) -> CrawlRow {
+    analyze_document(
+        url,
+        status,
+        headers,
+        html,
+        &UrlPolicy::default(),
+        AnalyzerRegistry::global(),
+    )
+}
+
+fn analyze_document(
+    url: &str,
+    status: u16,
+    headers: &[(&str, &str)],
+    html: &str,
+    policy: &UrlPolicy,
+    analyzers: &AnalyzerRegistry,
+) -> CrawlRow {
+    let header = |name: &str| {
+        headers
+            .iter()
+            .find(|(header_name, _)| header_name.trim().eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.trim().to_string())
+            .unwrap_or_default()
+    };
+    let url = normalize_crawl_url(url, policy).unwrap_or_else(|| url.trim().to_string());
+    let hosts = Url::parse(&url)
+        .ok()
+        .and_then(|u| u.host_str().map(str::to_ascii_lowercase))
+        .into_iter()
+        .collect::<Vec<_>>();
+
+    let mut row = unretrieved_row(url, String::new());
+    row.status = status;
+    row.retrieval_status = "retrieved".to_string();
+    row.last_modified = header("last-modified");
+    row.strict_transport_security = header("strict-transport-security");
+    row.content_security_policy = header("content-security-policy");
+    row.x_content_type_options = header("x-content-type-options");
+    row.x_frame_options = header("x-frame-options");
+    row.referrer_policy = header("referrer-policy");
+    row.cache_control = header("cache-control");
+    row.etag = header("etag");
+    row.vary = header("vary");
+    row.content_encoding = header("content-encoding");
+    apply_rendered_html_to_row(&mut row, html, Some(&hosts), policy, analyzers);
+    row
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct WordCountField;
+
+    impl Analyzer for WordCountField {
+        fn name(&self) -> &str {
+            "api_test_words"
+        }
+
+        fn analyze(&self, page: &PageContext<'_>, out: &mut AnalyzerOutput) {
+            out.fields
+                .insert("words".to_string(), page.row.word_count.to_string());
+        }
+    }
+
+    #[test]
+    fn builder_rejects_command_line_only_options() {
+        let cases: [&[&str]; 5] = [
+            &["--output", "crawl.csv"],
+            &["-o", "crawl.csv"],
+            &["--no-tui"],
+            &["--api=127.0.0.1:7878"],
+            &["--review", "crawl.csv"],
+        ];
+        for args in cases {
+            let builder = args
+                .iter()
+                .fold(CrawlBuilder::new("https://example.com"), |builder, arg| {
+                    builder.arg(*arg)
+                });
+            let Err(err) = builder.start() else {
+                panic!("{args:?} started a crawl");
+            };
+            assert!(err.ends_with("is not a crawl option"), "{args:?}: {err}");
+        }
+        let Err(err) = CrawlBuilder::new("https://example.com")
+            .arg("--list")
+            .arg("urls.txt")
+            .start()
+        else {
+            panic!("--list started a crawl");
+        };
+        assert!(err.contains("--list"), "{err}");
+    }
+
+    #[test]
+    fn builder_analyzes_with_its_own_analyzers_and_url_options() {
+        let html = "<html><head></head><body><p>Hello there</p></body></html>";
+        let global = analyze_html("https://example.com/a?b=2&a=1", 200, &[], html);
+        assert_eq!(global.url, "https://example.com/a?b=2&a=1");
+        assert!(global.issues.contains(&SeoIssue::MissingTitle));
+        assert_eq!(global.extra_field("words"), None);
+
+        let builder = CrawlBuilder::new("https://example.com")
+            .disable_analyzer("content")
+            .analyzer(WordCountField)
+            .arg("--sort-params");
+        let row = builder
+            .analyze_html("https://example.com/a?b=2&a=1", 200, &[], html)
+            .unwrap();
+        assert_eq!(row.url, "https://example.com/a?a=1&b=2");
+        assert!(row.is_retrieved());
+        assert!(!row.issues.contains(&SeoIssue::MissingTitle));
+        assert_eq!(row.extra_field("words"), Some("2"));
+        assert!(
+            !AnalyzerRegistry::global()
+                .names()
+                .iter()
+                .any(|(name, _)| name == "api_test_words")
+        );
+    }
+}
@@ -1,3 +1,5 @@
+use super::*;
+
 struct AbortTaskOnDrop(tokio::task::AbortHandle);
 
 impl Drop for AbortTaskOnDrop {
@@ -6,28 +8,211 @@ impl Drop for AbortTaskOnDrop {
     }
 }
 
+/// Forwards a row unless the crawl budget rejects it; rejected URLs are kept
+/// in the budget's frontier. An emitted page commits its collapsed variants.
+/// The facet cap and trap checks apply before a URL is requested, so a page
+/// that was fetched anyway is always kept.
+fn send_within_budget(
+    tx: &UnboundedSender<CrawlEvent>,
+    budget: &CrawlBudget,
+    policy: &UrlPolicy,
+    event: CrawlEvent,
+) {
+    let admitted = match &event {
+        CrawlEvent::Page { row, .. } => budget.record(&row.url, row.size as u64),
+        CrawlEvent::Unretrieved { url, .. } => budget.record(url, 0),
+        _ => true,
+    };
+    if !admitted {
+        return;
+    }
+    if let CrawlEvent::Page { row, .. } = &event {
+        policy.commit_variants(&row.url);
+    }
+    let _ = tx.send(event);
+}
+
+/// End-of-crawl summaries: the budget stop (if any), URL variants that
+/// normalization collapsed into one page, paths whose facets were capped and
+/// suspected crawl traps.
+fn send_crawl_reports(budget: &CrawlBudget, policy: &UrlPolicy, tx: &UnboundedSender<CrawlEvent>) {
+    if let Some(stop) = budget.report() {
+        let _ = tx.send(CrawlEvent::BudgetExhausted(stop));
+    }
+    let variants = policy.collapsed_variants();
+    if !variants.is_empty() {
+        let _ = tx.send(CrawlEvent::UrlVariants(variants));
+    }
+    let facet_caps = policy.facet_caps();
+    if !facet_caps.is_empty() {
+        let _ = tx.send(CrawlEvent::FacetCaps(facet_caps));
+    }
+    let traps = policy.traps.report();
+    if !traps.is_empty() {
+        let _ = tx.send(CrawlEvent::CrawlTraps(traps));
+    }
+}
+
 fn send_status(tx: &UnboundedSender<CrawlEvent>, message: impl Into<String>) {
     let _ = tx.send(CrawlEvent::Status(message.into()));
 }
 
-async fn process_retry_commands(
-    mut retry_rx: UnboundedReceiver<(RetryScope, Vec<String>)>,
-    retry_missing: usize,
+/// URLs added from the TUI or API since the last call. `added_rx` moves from
+/// loop to loop with the crawl, so whichever one is running takes them.
+fn take_added_urls(
+    added_rx: &mut UnboundedReceiver<Vec<String>>,
+    root_hosts: Option<&[String]>,
+    http: &HttpOptions,
+    tx: &UnboundedSender<CrawlEvent>,
+) -> Vec<String> {
+    let mut urls = Vec::new();
+    while let Ok(added) = added_rx.try_recv() {
+        http.frontier.merge_added();
+        urls.extend(queue_added_urls(added, root_hosts, http, tx));
+    }
+    urls
+}
+
+/// Normalizes added URLs and queues the ones in the crawl's hosts that are
+/// new to the frontier.
+fn queue_added_urls(
+    added: Vec<String>,
+    root_hosts: Option<&[String]>,
+    http: &HttpOptions,
+    tx: &UnboundedSender<CrawlEvent>,
+) -> Vec<String> {
+    let mut urls = Vec::new();
+    for raw in added {
+        match normalize_crawl_url(&raw, &http.url_policy) {
+            Some(url) if is_same_host(&url, root_hosts) => {
+                if http.frontier.queue(&url) {
+                    urls.push(url);
+                } else {
+                    send_status(
+                        tx,
+                        format!("Added URL {raw} is already queued, crawled or excluded; skipped"),
+                    );
+                }
+            }
+            _ => send_status(
+                tx,
+                format!("Added URL {raw} is outside the crawl scope; skipped"),
+            ),
+        }
+    }
+    if !urls.is_empty() {
+        send_status(tx, format!("Queued {} added URLs", urls.len()));
+    }
+    urls
+}
+
+/// What every fallback fetch of a crawl shares: retry settings, the live
+/// concurrency and shutdown flags, the crawl scope and request options.
+#[derive(Clone)]
+struct FetchContext {
+    retries: usize,
     retry_5xx: usize,
-    fetch_concurrency: Arc<AtomicUsize>,
+    concurrency: Arc<AtomicUsize>,
     shutdown_requested: Arc<AtomicBool>,
-    root_host: Option<String>,
+    root_hosts: Option<Vec<String>>,
+    http: HttpOptions,
+}
+
+impl FetchContext {
+    fn root_hosts(&self) -> Option<&[String]> {
+        self.root_hosts.as_deref()
+    }
+
+    fn shutting_down(&self) -> bool {
+        self.shutdown_requested.load(Ordering::Relaxed)
+    }
+}
+
+/// Clients for fallback fetches. Only `page` carries the credentials, one
+/// client per scope host with that host's cookies; `public_page` serves the
+/// rest, and the redirect probe adds them per request.
+#[derive(Clone)]
+struct FetchClients {
+    page: HashMap<String, spider::Client>,
+    public_page: spider::Client,
+    redirect: reqwest::Client,
+}
+
+impl FetchClients {
+    fn build(http: &HttpOptions) -> Result<Self, String> {
+        // Hosts that receive the same cookies share a client.
+        let mut by_cookie = HashMap::<Option<Vec<u8>>, spider::Client>::new();
+        let mut page = HashMap::new();
+        for host in &http.scope_hosts {
+            let credentials = http.credentials_for_host(host);
+            let key = credentials
+                .get(reqwest::header::COOKIE)
+                .map(|value| value.as_bytes().to_vec());
+            let client = match by_cookie.get(&key) {
+                Some(client) => client.clone(),
+                None => {
+                    let client = spider_client_builder(http)
+                        .default_headers(credentials)
+                        .timeout(Duration::from_secs(30))
+                        .build()
+                        .map_err(|e| format!("fetch client build failed: {e}"))?;
+                    by_cookie.insert(key, client.clone());
+                    client
+                }
+            };
+            page.insert(host.clone(), client);
+        }
+        let public_page = spider_client_builder(http)
+            .timeout(Duration::from_secs(30))
+            .build()
+            .map_err(|e| format!("fetch client build failed: {e}"))?;
+        let redirect = http_client_builder(http)
+            .redirect(reqwest::redirect::Policy::none())
+            .connect_timeout(Duration::from_secs(10))
+            .timeout(Duration::from_secs(25))
+            .pool_max_idle_per_host(32)
+            .build()
+            .map_err(|e| format!("redirect client build failed: {e}"))?;
+        Ok(FetchClients {
+            page,
+            public_page,
+            redirect,
+        })
+    }
+
+    fn page_client(&self, url: &str) -> &spider::Client {
+        Url::parse(url)
+            .ok()
+            .and_then(|url| self.page.get(&url.host_str()?.to_ascii_lowercase()))
+            .unwrap_or(&self.public_page)
+    }
+}
+
+async fn process_retry_commands(
+    mut retry_rx: UnboundedReceiver<(RetryScope, Vec<String>)>,
+    mut added_rx: UnboundedReceiver<Vec<String>>,
+    fetch: FetchContext,
     tx: &UnboundedSender<CrawlEvent>,
 ) {
+    let root_hosts = fetch.root_hosts();
     loop {
-        if shutdown_requested.load(Ordering::Relaxed) {
+        if fetch.shutting_down() {
             break;
         }
 
-        let Some((scope, urls)) = (tokio::select! {
-            cmd = retry_rx.recv() => cmd,
+        let Some((label, urls)) = (tokio::select! {
+            cmd = retry_rx.recv() => cmd.map(|(scope, urls)| (scope.label(), urls)),
+            Some(added) = added_rx.recv() => {
+                let urls = queue_added_urls(added, root_hosts, &fetch.http, tx);
+                if urls.is_empty() {
+                    // The UI expects the crawl to finish again once it sent them.
+                    let _ = tx.send(CrawlEvent::Finished);
+                    continue;
+                }
+                Some(("added URLs", urls))
+            }
             _ = tokio::time::sleep(Duration::from_millis(120)) => {
-                if shutdown_requested.load(Ordering::Relaxed) {
+                if fetch.shutting_down() {
                     None
                 } else {
                     continue;
@@ -39,8 +224,8 @@ async fn process_retry_commands(
 
         let mut normalized = urls
             .into_iter()
-            .filter_map(|url| normalize_crawl_url(&url))
-            .filter(|url| is_same_host(url, root_host.as_deref()))
+            .filter_map(|url| normalize_crawl_url(&url, &fetch.http.url_policy))
+            .filter(|url| is_same_host(url, root_hosts))
             .collect::<Vec<_>>();
         normalized.sort();
         normalized.dedup();
@@ -48,45 +233,78 @@ async fn process_retry_commands(
         if normalized.is_empty() {
             send_status(
                 tx,
-                format!("retry request '{}' skipped: no valid URLs", scope.label()),
+                format!("retry request '{label}' skipped: no valid URLs"),
             );
+            let _ = tx.send(CrawlEvent::Finished);
             continue;
         }
 
         send_status(
             tx,
             format!(
-                "retry request '{}' queued for {} URLs",
-                scope.label(),
+                "retry request '{label}' queued for {} URLs",
                 normalized.len()
             ),
         );
         fetch_missing_urls(
             normalized,
-            retry_missing,
-            retry_5xx,
-            fetch_concurrency.clone(),
-            shutdown_requested.clone(),
-            root_host.as_deref(),
+            &fetch,
+            &HashMap::new(),
+            &CrawlBudget::unlimited(),
+            &mut added_rx,
             tx,
         )
         .await;
-        if shutdown_requested.load(Ordering::Relaxed) {
+        if fetch.shutting_down() {
             break;
         }
         let _ = tx.send(CrawlEvent::Finished);
     }
 }
 
-async fn run_crawler(
+/// `list` holds the loaded `--list` entries; the caller reads the file so
+/// stdin is only consumed once.
+pub(crate) async fn run_crawler(
     cli: Cli,
+    analyzers: AnalyzerRegistry,
+    list: Option<Vec<(String, ListExpectation)>>,
+    frontier: Arc<CrawlFrontier>,
     tx: UnboundedSender<CrawlEvent>,
     mut control_rx: UnboundedReceiver<CrawlControl>,
 ) {
-    let Some(start_url) = cli.url.clone() else {
-        let _ = tx.send(CrawlEvent::Error("missing URL".to_string()));
-        let _ = tx.send(CrawlEvent::Finished);
-        return;
+    let mut http = match HttpOptions::from_cli(&cli) {
+        Ok(http) => http,
+        Err(err) => {
+            let _ = tx.send(CrawlEvent::Error(err));
+            let _ = tx.send(CrawlEvent::Finished);
+            return;
+        }
+    };
+    http.analyzers = Arc::new(analyzers);
+    http.frontier = frontier;
+    let budget = match CrawlBudget::from_cli(&cli) {
+        Ok(budget) => Arc::new(budget),
+        Err(err) => {
+            let _ = tx.send(CrawlEvent::Error(err));
+            let _ = tx.send(CrawlEvent::Finished);
+            return;
+        }
+    };
+    if budget.is_limited() {
+        send_status(&tx, format!("Crawl budget: {}", budget.describe()));
+    }
+    let login_script = match cli
+        .login_script
+        .as_deref()
+        .map(load_login_script)
+        .transpose()
+    {
+        Ok(script) => script,
+        Err(err) => {
+            let _ = tx.send(CrawlEvent::Error(err));
+            let _ = tx.send(CrawlEvent::Finished);
+            return;
+        }
     };
 
     let retry_missing = cli.retry_missing.max(1);
@@ -96,8 +314,13 @@ async fn run_crawler(
     )));
     let shutdown_requested = Arc::new(AtomicBool::new(false));
     let (retry_tx, retry_rx) = mpsc::unbounded_channel::<(RetryScope, Vec<String>)>();
+    let (added_tx, mut added_rx) = mpsc::unbounded_channel::<Vec<String>>();
     let control_concurrency = fetch_concurrency.clone();
     let control_shutdown = shutdown_requested.clone();
+    let control_pacer = http.pacer.clone();
+    let control_budget = budget.clone();
+    let control_frontier = http.frontier.clone();
+    let control_tx = tx.clone();
     let control_task = tokio::spawn(async move {
         while let Some(control) = control_rx.recv().await {
             match control {
@@ -107,47 +330,169 @@ async fn run_crawler(
                 CrawlControl::RetryUrls { scope, urls } => {
                     let _ = retry_tx.send((scope, urls));
                 }
+                CrawlControl::AddUrls(urls) => {
+                    let _ = added_tx.send(urls);
+                }
+                CrawlControl::ExcludePattern(pattern) => {
+                    let dropped = control_frontier.exclude(&pattern);
+                    send_status(
+                        &control_tx,
+                        format!(
+                            "Excluding URLs matching '{pattern}'; dropped {dropped} queued URLs"
+                        ),
+                    );
+                }
+                CrawlControl::Pause | CrawlControl::Resume => {
+                    let pause = matches!(control, CrawlControl::Pause);
+                    if control_pacer.is_paused() != pause {
+                        control_pacer.set_paused(pause);
+                        control_budget.set_paused(pause);
+                        send_status(
+                            &control_tx,
+                            if pause {
+                                "Crawl paused; requests in flight will finish".to_string()
+                            } else {
+                                "Crawl resumed".to_string()
+                            },
+                        );
+                    }
+                }
                 CrawlControl::Shutdown => {
                     control_shutdown.store(true, Ordering::Relaxed);
+                    // Let paused fetchers wake up and see the shutdown.
+                    control_pacer.set_paused(false);
                     break;
                 }
             }
         }
     });
     let _control_task_guard = AbortTaskOnDrop(control_task.abort_handle());
+    let rate_pacer = http.pacer.clone();
+    let rate_policy = http.url_policy.clone();
+    let rate_tx = tx.clone();
+    let rate_task = tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            let rates = rate_pacer.snapshot();
+            if !rates.is_empty() && rate_tx.send(CrawlEvent::HostRates(rates)).is_err() {
+                break;
+            }
+            for notice in rate_policy.traps.take_notices() {
+                let _ = rate_tx.send(CrawlEvent::Status(notice));
+            }
+        }
+    });
+    let _rate_task_guard = AbortTaskOnDrop(rate_task.abort_handle());
+
+    if let Some(entries) = list {
+        let source = cli.list.as_deref().unwrap_or("-");
+        let mut urls = Vec::new();
+        let mut queued = HashSet::new();
+        let mut expectations = HashMap::new();
+        for (raw, expectation) in entries {
+            let Some(url) = normalize_crawl_url(&raw, &http.url_policy) else {
+                send_status(
+                    &tx,
+                    format!("list entry skipped (not an http(s) URL): {raw}"),
+                );
+                continue;
+            };
+            if !expectation.is_empty() {
+                expectations.insert(url.clone(), expectation);
+            }
+            if queued.insert(url.clone()) {
+                urls.push(url);
+            }
+        }
+        // Links to the listed hosts (and --allow-host) count as internal.
+        let list_hosts = crawl_scope_hosts(&urls, &cli.allow_host);
+        http.extend_scope(urls.iter().map(String::as_str));
 
-    let mut website = Website::new(&start_url);
-    let root_host = Url::parse(&start_url)
-        .ok()
-        .and_then(|u| u.host_str().map(|h| h.to_string()));
-
-    website.configuration.subdomains = cli.subdomains;
-    website.configuration.tld = cli.tld;
-    website.configuration.return_page_links = true;
-    website.configuration.respect_robots_txt = cli.respect_robots;
-    website.configuration.full_resources = cli.full_resources;
-
-    // 0 is "no limit" in spider and avoids missing deep paths by default.
-    website.configuration.with_depth(cli.depth.unwrap_or(0));
+        send_status(
+            &tx,
+            format!(
+                "List mode: auditing {} URLs from {} ({} with expectations)",
+                urls.len(),
+                source,
+                expectations.len()
+            ),
+        );
+        let _ = tx.send(CrawlEvent::Stats {
+            discovered: urls.len(),
+        });
+        if cli.respect_robots {
+            apply_robots_crawl_delays(&urls, &http, cli.user_agent.as_deref(), &tx).await;
+        }
+        let fetch = FetchContext {
+            retries: retry_missing,
+            retry_5xx,
+            concurrency: fetch_concurrency,
+            shutdown_requested,
+            root_hosts: Some(list_hosts),
+            http,
+        };
+        fetch_missing_urls(urls, &fetch, &expectations, &budget, &mut added_rx, &tx).await;
+        send_crawl_reports(&budget, &fetch.http.url_policy, &tx);
+        let _ = tx.send(CrawlEvent::Finished);
+        process_retry_commands(retry_rx, added_rx, fetch, &tx).await;
+        return;
+    }
 
-    if let Some(delay) = cli.delay_ms {
-        website.configuration.with_delay(delay);
+    let Some(start_url) = cli.url.first().cloned() else {
+        let _ = tx.send(CrawlEvent::Error("missing URL".to_string()));
+        let _ = tx.send(CrawlEvent::Finished);
+        return;
+    };
+    let seed_urls = cli.url.clone();
+    let root_hosts = Some(crawl_scope_hosts(&seed_urls, &cli.allow_host));
+    if seed_urls.len() > 1 || !cli.allow_host.is_empty() {
+        send_status(
+            &tx,
+            format!(
+                "Crawl scope: {} seeds, hosts {}",
+                seed_urls.len(),
+                root_hosts.as_deref().unwrap_or_default().join(", ")
+            ),
+        );
     }
-    if let Some(ref ua) = cli.user_agent {
-        website.configuration.with_user_agent(Some(ua));
+    if cli.respect_robots {
+        let origins = scope_origins(&seed_urls, root_hosts.as_deref().unwrap_or_default());
+        apply_robots_crawl_delays(&origins, &http, cli.user_agent.as_deref(), &tx).await;
     }
-    let redirect_client = reqwest::Client::builder()
-        .redirect(reqwest::redirect::Policy::none())
-        .timeout(Duration::from_secs(20))
-        .build()
-        .ok();
 
     let mut enable_webdriver = cli.webdriver || cli.webdriver_headless;
     let mut webdriver_url = cli.webdriver_url.clone();
     let mut driver_process: Option<Child> = None;
     let mut active_browser = cli.webdriver_browser;
-    if enable_webdriver {
-        match prepare_webdriver_backend(&cli, &start_url, &webdriver_url, &tx).await {
+    if let Some(proxy) = http.proxy_label() {
+        send_status(&tx, format!("Routing requests through proxy {proxy}"));
+        let bypassed = root_hosts
+            .as_deref()
+            .unwrap_or_default()
+            .iter()
+            .filter(|host| http.proxy_for(Some(host.as_str())).is_none())
+            .cloned()
+            .collect::<Vec<_>>();
+        if !bypassed.is_empty() {
+            send_status(
+                &tx,
+                format!("Proxy bypassed for {} (--no-proxy)", bypassed.join(", ")),
+            );
+        }
+        if (enable_webdriver || login_script.is_some())
+            && http
+                .proxy
+                .as_ref()
+                .is_some_and(|proxy| !proxy.username().is_empty())
+        {
+            send_status(
+                &tx,
+                "WebDriver browsers cannot use proxy credentials; the browser connects unauthenticated",
+            );
+        }
+    }
+    if enable_webdriver || login_script.is_some() {
+        match prepare_webdriver_backend(&cli, &http, &start_url, &webdriver_url, &tx).await {
             Ok((ready_endpoint, managed_child, browser)) => {
                 webdriver_url = ready_endpoint;
                 driver_process = managed_child;
@@ -155,6 +500,13 @@ async fn run_crawler(
                 send_status(&tx, format!("WebDriver active browser: {:?}", active_browser));
             }
             Err(err) => {
+                if login_script.is_some() {
+                    let _ = tx.send(CrawlEvent::Error(format!(
+                        "WebDriver unavailable ({err}); --login-script needs a browser session"
+                    )));
+                    let _ = tx.send(CrawlEvent::Finished);
+                    return;
+                }
                 if cli.webdriver_required {
                     let _ = tx.send(CrawlEvent::Error(format!(
                         "WebDriver unavailable ({err}); aborting because --webdriver-required is set"
@@ -174,20 +526,112 @@ async fn run_crawler(
             }
         }
     }
+    if let Some(script) = login_script.as_ref() {
+        match webdriver_login_cookies(
+            &webdriver_url,
+            active_browser,
+            cli.webdriver_headless,
+            script,
+            root_hosts.as_deref(),
+            &mut http,
+        )
+        .await
+        {
+            Ok(count) => send_status(
+                &tx,
+                format!("Login script completed; reusing {count} session cookies"),
+            ),
+            Err(err) => {
+                let _ = tx.send(CrawlEvent::Error(format!(
+                    "login script failed: {}",
+                    http.redact(&err)
+                )));
+                let _ = tx.send(CrawlEvent::Finished);
+                stop_webdriver(driver_process.take());
+                return;
+            }
+        }
+        if !enable_webdriver {
+            stop_webdriver(driver_process.take());
+        }
+    }
+    if http.is_active() {
+        send_status(&tx, format!("Authenticated crawl: {}", http.describe()));
+        let host_specific = http.cookies.len() - http.shared_cookies().count();
+        if host_specific > 0 {
+            send_status(
+                &tx,
+                format!(
+                    "{host_specific} cookies apply to only some crawl hosts; spider sends none of them, fallback fetches send each to its own host"
+                ),
+            );
+        }
+        if cli.subdomains || cli.tld {
+            send_status(
+                &tx,
+                "--subdomains and --tld are ignored with credentials; add hosts with --allow-host"
+                    .to_string(),
+            );
+        }
+    }
+    let fetch = FetchContext {
+        retries: retry_missing,
+        retry_5xx,
+        concurrency: fetch_concurrency.clone(),
+        shutdown_requested: shutdown_requested.clone(),
+        root_hosts: root_hosts.clone(),
+        http: http.clone(),
+    };
+    let redirect_client = http_client_builder(&http)
+        .redirect(reqwest::redirect::Policy::none())
+        .timeout(Duration::from_secs(20))
+        .build()
+        .ok();
+
+    if let Some(previous_path) = cli.incremental.as_deref() {
+        let previous = match load_rows_from_file(previous_path) {
+            Ok(rows) => rows,
+            Err(err) => {
+                let _ = tx.send(CrawlEvent::Error(format!(
+                    "failed to load previous export {previous_path}: {err}"
+                )));
+                let _ = tx.send(CrawlEvent::Finished);
+                stop_webdriver(driver_process.take());
+                return;
+            }
+        };
+        stop_webdriver(driver_process.take());
+        send_status(
+            &tx,
+            format!(
+                "Incremental crawl against {} rows from {previous_path}",
+                previous.len()
+            ),
+        );
+        incremental_crawl(
+            &seed_urls,
+            previous,
+            cli.depth.unwrap_or(0),
+            &fetch,
+            &budget,
+            &mut added_rx,
+            &tx,
+        )
+        .await;
+        send_crawl_reports(&budget, &http.url_policy, &tx);
+        let _ = tx.send(CrawlEvent::Finished);
+        process_retry_commands(retry_rx, added_rx, fetch, &tx).await;
+        return;
+    }
+
     if enable_webdriver {
-        let depth_limit = cli.depth.unwrap_or(0);
         match browser_discover_and_fetch(
+            &cli,
             &webdriver_url,
             active_browser,
-            cli.webdriver_headless,
-            &start_url,
-            depth_limit,
-            cli.seed_sitemap,
-            retry_missing,
-            retry_5xx,
-            fetch_concurrency.clone(),
-            shutdown_requested.clone(),
-            root_host.as_deref(),
+            &fetch,
+            &budget,
+            &mut added_rx,
             &tx,
         )
         .await
@@ -202,18 +646,10 @@ async fn run_crawler(
                         reason: "browser backend discovered zero URLs".to_string(),
                     });
                 }
+                send_crawl_reports(&budget, &http.url_policy, &tx);
                 let _ = tx.send(CrawlEvent::Finished);
                 stop_webdriver(driver_process.take());
-                process_retry_commands(
-                    retry_rx,
-                    retry_missing,
-                    retry_5xx,
-                    fetch_concurrency.clone(),
-                    shutdown_requested.clone(),
-                    root_host.clone(),
-                    &tx,
-                )
-                .await;
+                process_retry_commands(retry_rx, added_rx, fetch, &tx).await;
                 return;
             }
             Err(err) => {
@@ -222,7 +658,10 @@ async fn run_crawler(
                     stop_webdriver(driver_process.take());
                     return;
                 }
-                send_status(&tx, format!("Browser discovery backend failed: {err}"));
+                send_status(
+                    &tx,
+                    format!("Browser discovery backend failed: {}", http.redact(&err)),
+                );
                 stop_webdriver(driver_process.take());
                 if cli.webdriver_required {
                     let _ = tx.send(CrawlEvent::Unretrieved {
@@ -236,171 +675,519 @@ async fn run_crawler(
             }
         }
     }
-    website.configuration.with_webdriver_config(None);
-
-    let mut subscription = match website.subscribe(cli.channel_capacity.max(1)) {
-        Some(s) => s,
-        None => {
-            let _ = tx.send(CrawlEvent::Error(
-                "spider sync subscription unavailable (enable `sync` feature)".to_string(),
-            ));
-            website.crawl().await;
-            let discovered = website.get_links().len();
-            let _ = tx.send(CrawlEvent::Stats { discovered });
-            let _ = tx.send(CrawlEvent::Finished);
-            stop_webdriver(driver_process);
-            return;
-        }
-    };
-
     let seed_sitemap = cli.seed_sitemap;
     let mut seen_urls = HashSet::<String>::new();
     let mut discovered_from_pages = HashSet::<String>::new();
     let mut retryable_5xx_urls = HashSet::<String>::new();
-    let crawl_task = tokio::spawn(async move {
-        if seed_sitemap {
-            let _ = tokio::time::timeout(Duration::from_secs(45), website.crawl_sitemap()).await;
+    let mut spider_links = Vec::<String>::new();
+    let mut added_urls = Vec::<String>::new();
+    // One Website crawls every seed so the hosts share spider's visited set.
+    let mut seed_links = Vec::<String>::new();
+    if seed_urls.len() > 1 {
+        seed_links.extend(seed_urls.iter().cloned());
+        if seed_sitemap && let Ok(client) = http_client_builder(&http).build() {
+            for seed in &seed_urls[1..] {
+                seed_links.extend(
+                    discover_sitemap_seed_urls(&client, &http, seed, root_hosts.as_deref()).await,
+                );
+            }
+        }
+    }
+    'spider: {
+        if shutdown_requested.load(Ordering::Relaxed) || budget.stopped().is_some() {
+            break 'spider;
         }
-        website.crawl().await;
+        let mut website = configure_website(&cli, &seed_urls, &http, &budget);
+        let mut subscription = match website.subscribe(cli.channel_capacity.max(1)) {
+            Some(s) => s,
+            None => {
+                let _ = tx.send(CrawlEvent::Error(
+                    "spider sync subscription unavailable (enable `sync` feature)".to_string(),
+                ));
+                website.crawl().await;
+                spider_links.extend(website.get_links().into_iter().map(|u| u.to_string()));
+                break 'spider;
+            }
+        };
 
-        website
-            .get_links()
-            .into_iter()
-            .map(|u| u.to_string())
-            .collect::<Vec<_>>()
-    });
+        // Pause/resume reach spider through its control channel, keyed by target.
+        let spider_target = website.target_id();
+        let mut spider_paused = false;
+        // spider has no per-host gate, so a 429/503 backoff pauses the crawl.
+        let mut backoff_until: Option<Instant> = None;
+        let spider_queue = website.queue(cli.channel_capacity.max(1));
+        // spider resolves relative links against its start host, so links
+        // found on the other hosts' pages are queued from our own parse.
+        let base_host = Url::parse(website.get_url().inner())
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_ascii_lowercase));
+        let crawl_task = tokio::spawn(async move {
+            if seed_sitemap {
+                let _ =
+                    tokio::time::timeout(Duration::from_secs(45), website.crawl_sitemap()).await;
+            }
+            // spider skips its start URL when extra links are set, so the
+            // first seed is one of them.
+            if !seed_links.is_empty() {
+                website.set_extra_links(seed_links.into_iter().map(Into::into).collect());
+                website.persist_links();
+            }
+            website.crawl().await;
 
-    loop {
-        if shutdown_requested.load(Ordering::Relaxed) {
-            break;
-        }
-        tokio::select! {
-            recv = subscription.recv() => match recv {
-            Ok(page) => {
+            website
+                .get_links()
+                .into_iter()
+                .map(|u| u.to_string())
+                .collect::<Vec<_>>()
+        });
+
+        let mut spider_done = false;
+        let mut probes = JoinSet::<Page>::new();
+        loop {
+            if shutdown_requested.load(Ordering::Relaxed) || budget.stopped().is_some() {
+                break;
+            }
+            if spider_done && probes.is_empty() {
+                break;
+            }
+            if backoff_until.is_some_and(|until| Instant::now() >= until) {
+                backoff_until = None;
+            }
+            if (http.pacer.is_paused() || backoff_until.is_some()) != spider_paused {
+                spider_paused = !spider_paused;
+                if spider_paused {
+                    spider::utils::pause(&spider_target).await;
+                } else {
+                    spider::utils::resume(&spider_target).await;
+                }
+            }
+            // Added URLs also join the recovery pass in case spider is done.
+            for url in take_added_urls(&mut added_rx, root_hosts.as_deref(), &http, &tx) {
+                if seen_urls.contains(&url) {
+                    http.frontier.complete(&url);
+                    send_status(&tx, format!("Added URL {url} was already crawled; skipped"));
+                    continue;
+                }
+                if let Some(queue) = spider_queue.as_ref() {
+                    let _ = queue.send(url.clone());
+                }
+                added_urls.push(url);
+            }
+            // Compression probes run beside the loop; their pages come back here.
+            let (mut page, probed) = tokio::select! {
+                recv = subscription.recv(), if !spider_done => match recv {
+                    Ok(page) => (page, false),
+                    Err(RecvError::Lagged(skipped)) => {
+                        let _ = tx.send(CrawlEvent::Error(format!(
+                            "subscription lagged, skipped {skipped} pages; increase --channel-capacity"
+                        )));
+                        continue;
+                    }
+                    Err(RecvError::Closed) => {
+                        spider_done = true;
+                        continue;
+                    }
+                },
+                Some(joined) = probes.join_next(), if !probes.is_empty() => match joined {
+                    Ok(page) => (page, true),
+                    Err(_) => continue,
+                },
+                _ = tokio::time::sleep(Duration::from_millis(120)) => continue,
+            };
+            let requested_url = normalize_crawl_url(page.get_url(), &http.url_policy)
+                .unwrap_or_else(|| page.get_url().to_string());
+            if !probed {
+                http.pacer.record_request(page.get_url());
+                if let Some(wait) = http.pacer.observe(
+                    page.get_url(),
+                    page.status_code.as_u16(),
+                    header_value(&page, "retry-after").as_deref(),
+                ) {
+                    let until = Instant::now() + wait;
+                    backoff_until = Some(backoff_until.map_or(until, |current| current.max(until)));
+                    send_status(
+                        &tx,
+                        format!(
+                            "{} answered {}; crawl paused for {}s",
+                            HostPacer::host_key(page.get_url()),
+                            page.status_code.as_u16(),
+                            wait.as_secs()
+                        ),
+                    );
+                }
+                // spider cannot drop a link it has already queued, so a page
+                // excluded after that is fetched and dropped here.
+                if http.frontier.is_excluded(&requested_url) {
+                    http.frontier.complete(&requested_url);
+                    continue;
+                }
                 if let Some(client) = redirect_client.as_ref() {
                     let requested = page.get_url().to_string();
                     let final_url = page.get_url_final().to_string();
                     if requested != final_url
-                        && let Ok((redirect_rows, _)) =
-                            raw_redirect_rows(client, &requested, 8).await
+                        && let Ok(trace) = raw_redirect_rows(client, &http, &requested, 8).await
                     {
-                        for (row, discovered_links) in redirect_rows {
-                            let filtered_links =
-                                filter_crawlable_links(discovered_links, root_host.as_deref());
-                            let _ = tx.send(CrawlEvent::Page {
-                                row,
-                                discovered_links: filtered_links,
-                            });
+                        if let Some(encoding) = trace.content_encoding.as_deref()
+                            && trace.final_url == final_url
+                        {
+                            set_content_encoding(&mut page, encoding);
+                        }
+                        for (row, discovered_links) in trace.rows {
+                            let filtered_links = filter_crawlable_links(
+                                discovered_links,
+                                root_hosts.as_deref(),
+                                &http.url_policy,
+                            );
+                            send_within_budget(
+                                &tx,
+                                &budget,
+                                &http.url_policy,
+                                CrawlEvent::Page {
+                                    row: Box::new(row),
+                                    discovered_links: filtered_links,
+                                },
+                            );
                         }
                     }
+                    if http.probe_compression && needs_content_encoding(&page) {
+                        let credentials = http.credentials_for(page.get_url_final());
+                        probes.spawn(probe_content_encoding(page, client.clone(), credentials));
+                        continue;
+                    }
                 }
-                let (mut row, discovered_links) = page_to_row(&page, root_host.as_deref());
-                let filtered_links = filter_crawlable_links(discovered_links, root_host.as_deref());
-                row.link_count = filtered_links.len();
-                let row_url = row.url.clone();
-                seen_urls.insert(row_url.clone());
-                if (500..=599).contains(&row.status) {
-                    retryable_5xx_urls.insert(row_url.clone());
-                } else {
-                    retryable_5xx_urls.remove(&row_url);
-                }
-                for link in &filtered_links {
-                    discovered_from_pages.insert(link.clone());
-                }
-                let _ = tx.send(CrawlEvent::Page {
-                    row,
-                    discovered_links: filtered_links,
-                });
             }
-            Err(RecvError::Lagged(skipped)) => {
-                let _ = tx.send(CrawlEvent::Error(format!(
-                    "subscription lagged, skipped {skipped} pages; increase --channel-capacity"
-                )));
+            let (mut row, discovered_links) = page_to_row(
+                &page,
+                root_hosts.as_deref(),
+                &http.url_policy,
+                &http.analyzers,
+            );
+            // From here on `seen_urls` keeps the page from being queued again.
+            http.frontier.complete(&requested_url);
+            seen_urls.insert(requested_url);
+            // A redirect can also end on an excluded URL.
+            if http.frontier.is_excluded(&row.url) {
                 continue;
             }
-            Err(RecvError::Closed) => break,
-            },
-            _ = tokio::time::sleep(Duration::from_millis(120)) => {}
+            let filtered_links =
+                filter_crawlable_links(discovered_links, root_hosts.as_deref(), &http.url_policy);
+            row.link_count = filtered_links.len();
+            let row_url = row.url.clone();
+            http.frontier.complete(&row_url);
+            seen_urls.insert(row_url.clone());
+            if (500..=599).contains(&row.status) {
+                retryable_5xx_urls.insert(row_url.clone());
+            } else {
+                retryable_5xx_urls.remove(&row_url);
+            }
+            let foreign_page = http.scope_hosts.len() > 1
+                && Url::parse(&row_url)
+                    .ok()
+                    .and_then(|url| url.host_str().map(str::to_ascii_lowercase))
+                    != base_host;
+            // spider does not follow the links of a page its should-crawl
+            // hook rejected, so they are not queued either.
+            let follow_links = !page.blocked_crawl;
+            for link in &filtered_links {
+                if follow_links
+                    && !seen_urls.contains(link)
+                    && http.frontier.queue(link)
+                    && foreign_page
+                    && let Some(queue) = spider_queue.as_ref()
+                {
+                    let _ = queue.send(link.clone());
+                }
+                discovered_from_pages.insert(link.clone());
+            }
+            send_within_budget(
+                &tx,
+                &budget,
+                &http.url_policy,
+                CrawlEvent::Page {
+                    row: Box::new(row),
+                    discovered_links: filtered_links,
+                },
+            );
+        }
+
+        if shutdown_requested.load(Ordering::Relaxed) || budget.stopped().is_some() {
+            crawl_task.abort();
+            let _ = crawl_task.await;
+            break 'spider;
+        }
+        match crawl_task.await {
+            Ok(discovered_urls) => spider_links.extend(discovered_urls),
+            Err(err) => {
+                let _ = tx.send(CrawlEvent::Error(format!("crawl task failed: {err}")));
+            }
         }
     }
 
     if shutdown_requested.load(Ordering::Relaxed) {
-        crawl_task.abort();
-        let _ = crawl_task.await;
         let _ = tx.send(CrawlEvent::Finished);
         stop_webdriver(driver_process.take());
         return;
     }
 
-    match crawl_task.await {
-        Ok(discovered_urls) => {
-            let mut candidate_urls = discovered_urls;
-            candidate_urls.extend(discovered_from_pages);
-            candidate_urls.push(start_url.clone());
+    let mut candidate_urls = spider_links;
+    candidate_urls.extend(discovered_from_pages);
+    candidate_urls.extend(seed_urls.iter().cloned());
+    candidate_urls.extend(added_urls);
+    candidate_urls.extend(take_added_urls(
+        &mut added_rx,
+        root_hosts.as_deref(),
+        &http,
+        &tx,
+    ));
 
-            let mut crawlable_candidates = candidate_urls
-                .into_iter()
-                .filter_map(|url| normalize_crawl_url(&url))
-                .filter(|url| is_same_host(url, root_host.as_deref()))
-                .collect::<Vec<_>>();
-            crawlable_candidates.sort();
-            crawlable_candidates.dedup();
-            let discovered = crawlable_candidates.len().max(seen_urls.len());
-            let _ = tx.send(CrawlEvent::Stats { discovered });
-            let mut recovery_urls = crawlable_candidates
-                .into_iter()
-                .filter(|url| !seen_urls.contains(url))
-                .collect::<Vec<_>>();
-            let missing_count = recovery_urls.len();
-            let mut retry_5xx_urls = retryable_5xx_urls.into_iter().collect::<Vec<_>>();
-            retry_5xx_urls.sort();
-            let retry_5xx_count = retry_5xx_urls.len();
-            recovery_urls.extend(retry_5xx_urls);
-            recovery_urls.sort();
-            recovery_urls.dedup();
-
-            if enable_webdriver && seen_urls.is_empty() {
-                let _ = tx.send(CrawlEvent::Error(
-                    "WebDriver crawl returned zero pages; falling back to HTTP retrieval for discovered/seed URLs"
-                        .to_string(),
-                ));
-            }
+    let mut crawlable_candidates = candidate_urls
+        .into_iter()
+        .filter_map(|url| normalize_crawl_url(&url, &http.url_policy))
+        .filter(|url| is_same_host(url, root_hosts.as_deref()) && !http.frontier.is_excluded(url))
+        .collect::<Vec<_>>();
+    crawlable_candidates.sort();
+    crawlable_candidates.dedup();
+    let discovered = crawlable_candidates.len().max(seen_urls.len());
+    let _ = tx.send(CrawlEvent::Stats { discovered });
+    let mut recovery_urls = crawlable_candidates
+        .into_iter()
+        .filter(|url| !seen_urls.contains(url))
+        .collect::<Vec<_>>();
+    let missing_count = recovery_urls.len();
+    let mut retry_5xx_urls = retryable_5xx_urls.into_iter().collect::<Vec<_>>();
+    retry_5xx_urls.sort();
+    let retry_5xx_count = retry_5xx_urls.len();
+    recovery_urls.extend(retry_5xx_urls);
+    recovery_urls.sort();
+    recovery_urls.dedup();
+
+    if enable_webdriver && seen_urls.is_empty() {
+        let _ = tx.send(CrawlEvent::Error(
+            "WebDriver crawl returned zero pages; falling back to HTTP retrieval for discovered/seed URLs"
+                .to_string(),
+        ));
+    }
 
-            if !recovery_urls.is_empty() {
-                let _ = tx.send(CrawlEvent::Error(format!(
-                    "reconciling {} missing URLs and requeueing {} pages with 5xx responses",
-                    missing_count, retry_5xx_count
-                )));
-                fetch_missing_urls(
-                    recovery_urls,
-                    retry_missing,
-                    retry_5xx,
-                    fetch_concurrency.clone(),
-                    shutdown_requested.clone(),
-                    root_host.as_deref(),
-                    &tx,
-                )
-                .await;
-            }
+    if budget.stopped().is_some() {
+        budget.defer(recovery_urls.drain(..));
+    }
+    if !recovery_urls.is_empty() {
+        let _ = tx.send(CrawlEvent::Error(format!(
+            "reconciling {} missing URLs and requeueing {} pages with 5xx responses",
+            missing_count, retry_5xx_count
+        )));
+        fetch_missing_urls(
+            recovery_urls,
+            &fetch,
+            &HashMap::new(),
+            &budget,
+            &mut added_rx,
+            &tx,
+        )
+        .await;
+    }
 
-            let _ = tx.send(CrawlEvent::Finished);
+    send_crawl_reports(&budget, &http.url_policy, &tx);
+    let _ = tx.send(CrawlEvent::Finished);
+    stop_webdriver(driver_process);
+    process_retry_commands(retry_rx, added_rx, fetch, &tx).await;
+}
+
+/// Hosts of the seeds and `--allow-host`, lowercased and deduplicated.
+pub(crate) fn crawl_scope_hosts(seed_urls: &[String], allow_hosts: &[String]) -> Vec<String> {
+    let mut hosts = seed_urls
+        .iter()
+        .filter_map(|url| {
+            Url::parse(url)
+                .ok()?
+                .host_str()
+                .map(|h| h.to_ascii_lowercase())
+        })
+        .chain(
+            allow_hosts
+                .iter()
+                .map(|host| host.trim().to_ascii_lowercase())
+                .filter(|host| !host.is_empty()),
+        )
+        .collect::<Vec<_>>();
+    hosts.sort();
+    hosts.dedup();
+    hosts
+}
+
+/// Origins of the crawl scope: each seed keeps its own scheme and port, and
+/// `--allow-host` hosts take the scheme of the first seed.
+fn scope_origins(seed_urls: &[String], scope_hosts: &[String]) -> Vec<String> {
+    let seeds = seed_urls
+        .iter()
+        .filter_map(|url| Url::parse(url).ok())
+        .collect::<Vec<_>>();
+    let scheme = seeds.first().map(|url| url.scheme()).unwrap_or("https");
+    let mut origins = Vec::<String>::new();
+    for origin in seeds.iter().map(|url| url.origin().ascii_serialization()) {
+        if !origins.contains(&origin) {
+            origins.push(origin);
+        }
+    }
+    for host in scope_hosts {
+        let covered = seeds
+            .iter()
+            .any(|url| url.host_str().is_some_and(|h| h.eq_ignore_ascii_case(host)));
+        if !covered {
+            origins.push(format!("{scheme}://{host}"));
+        }
+    }
+    origins
+}
+
+/// spider's should-crawl hook. A fetched page that is itself a capped facet
+/// or a suspected trap is still exported, but its links are not followed.
+/// spider has no block list that can change during a crawl, so a page
+/// excluded after its link was queued is fetched anyway; it is not followed
+/// here and the crawl loop drops it.
+fn follows_page_links(url: &str, policy: &UrlPolicy, frontier: &CrawlFrontier) -> bool {
+    let url = normalize_crawl_url(url, policy).unwrap_or_else(|| url.to_string());
+    !frontier.is_excluded(&url) && policy.admit(&url)
+}
+
+fn configure_website(
+    cli: &Cli,
+    seed_urls: &[String],
+    http: &HttpOptions,
+    budget: &CrawlBudget,
+) -> Website {
+    let mut website = Website::new(seed_urls.first().map(String::as_str).unwrap_or_default());
+    // spider sends its headers to every host it crawls, so a crawl with
+    // credentials stays on the scope hosts.
+    website.configuration.subdomains = cli.subdomains && !http.is_active();
+    website.configuration.tld = cli.tld && !http.is_active();
+    website.configuration.return_page_links = true;
+    website.configuration.respect_robots_txt = cli.respect_robots;
+    website.configuration.full_resources = cli.full_resources;
+    let policy = http.url_policy.clone();
+    let frontier = http.frontier.clone();
+    website.with_on_should_crawl_callback_closure(Some(move |page: &Page| {
+        follows_page_links(page.get_url(), &policy, &frontier)
+    }));
+    // spider fetches links in the form the URL policy gives them and calls
+    // this just before each request.
+    let policy = http.url_policy.clone();
+    let frontier = http.frontier.clone();
+    website.set_on_link_find(move |link, html| {
+        let Some(normalized) = normalize_crawl_url(link.as_ref(), &policy) else {
+            return (link, html);
+        };
+        frontier.dequeue(&normalized);
+        (normalized.into(), html)
+    });
+
+    // 0 is "no limit" in spider and avoids missing deep paths by default.
+    website.configuration.with_depth(cli.depth.unwrap_or(0));
+
+    // --rate-limit and --delay-ms; spider applies robots.txt Crawl-delay itself.
+    let delay = http.pacer.base_interval.as_millis() as u64;
+    if delay > 0 {
+        website.configuration.with_delay(delay);
+    }
+    if let Some(ref ua) = cli.user_agent {
+        website.configuration.with_user_agent(Some(ua));
+    }
+    if http.is_active() {
+        website
+            .configuration
+            .with_headers(Some(http.shared_credentials()));
+    }
+    // Let spider follow links into the other in-scope hosts; it matches
+    // these by bare host, so any scheme or port is allowed.
+    if http.scope_hosts.len() > 1 {
+        website
+            .configuration
+            .with_external_domains(Some(http.scope_hosts.iter().cloned()));
+    }
+    // Spider stops on its own budget; CrawlBudget still has the final say on rows.
+    let mut spider_budget = spider::hashbrown::HashMap::new();
+    let remaining_pages = budget
+        .remaining_pages()
+        .map(|pages| pages.min(u32::MAX as usize) as u32);
+    if let Some(pages) = remaining_pages {
+        spider_budget.insert("*", pages.max(1));
+    }
+    for (path, limit) in &budget.dir_limits {
+        spider_budget.insert(path.as_str(), (*limit).min(u32::MAX as usize) as u32);
+    }
+    if !spider_budget.is_empty() {
+        website.configuration.with_budget(Some(spider_budget));
+    }
+    website.configuration.with_webdriver_config(None);
+    // spider follows redirects to any host; keep the credentials in scope.
+    // The proxy goes on the client so --no-proxy applies per host.
+    if http.is_active() || http.proxy.is_some() {
+        let mut builder = website
+            .configure_http_client_builder()
+            .redirect(spider_redirect_policy(http));
+        if let Some(proxy) = spider_proxy(http) {
+            builder = builder.proxy(proxy);
+        }
+        if let Ok(client) = builder.build() {
+            website.set_http_client(client);
+        }
+    }
+    website
+}
+
+/// Reads robots.txt for each origin and applies its `Crawl-delay` to the
+/// pacer so fallback, browser and redirect fetches respect it as well.
+async fn apply_robots_crawl_delays(
+    urls: &[String],
+    http: &HttpOptions,
+    user_agent: Option<&str>,
+    tx: &UnboundedSender<CrawlEvent>,
+) {
+    let Ok(client) = http_client_builder(http)
+        .timeout(Duration::from_secs(15))
+        .build()
+    else {
+        return;
+    };
+    let mut origins = urls
+        .iter()
+        .filter_map(|url| Url::parse(url).ok())
+        .filter_map(|url| {
+            let host = url.host_str()?.to_ascii_lowercase();
+            Some((url.origin().ascii_serialization(), host))
+        })
+        .collect::<Vec<_>>();
+    origins.sort();
+    origins.dedup();
+    for (origin, host) in origins {
+        let robots_url = format!("{origin}/robots.txt");
+        let Ok(response) = client
+            .get(&robots_url)
+            .headers(http.credentials_for(&robots_url))
+            .send()
+            .await
+        else {
+            continue;
+        };
+        if !response.status().is_success() {
+            continue;
         }
-        Err(err) => {
-            let _ = tx.send(CrawlEvent::Error(format!("crawl task failed: {err}")));
-            let _ = tx.send(CrawlEvent::Finished);
+        let Ok(body) = response.text().await else {
+            continue;
+        };
+        if let Some(delay) = parse_robots_crawl_delay(&body, user_agent.unwrap_or_default())
+            && !delay.is_zero()
+        {
+            http.pacer.set_crawl_delay(&host, delay);
+            send_status(
+                tx,
+                format!(
+                    "robots.txt Crawl-delay for {host}: {:.1}s",
+                    delay.as_secs_f64()
+                ),
+            );
         }
     }
-    stop_webdriver(driver_process);
-    process_retry_commands(
-        retry_rx,
-        retry_missing,
-        retry_5xx,
-        fetch_concurrency.clone(),
-        shutdown_requested.clone(),
-        root_host.clone(),
-        &tx,
-    )
-    .await;
 }
 
 fn webdriver_reachable(endpoint: &str) -> bool {
@@ -423,6 +1210,7 @@ fn webdriver_reachable(endpoint: &str) -> bool {
 
 async fn start_webdriver(
     cli: &Cli,
+    http: &HttpOptions,
     browser: BrowserArg,
     endpoint: &str,
     tx: &UnboundedSender<CrawlEvent>,
@@ -446,7 +1234,7 @@ async fn start_webdriver(
         match browser {
             BrowserArg::Chrome => {
                 let mut chrome_candidates = Vec::new();
-                if let Ok(bundle) = ensure_chromedriver_bundle(tx).await {
+                if let Ok(bundle) = ensure_chromedriver_bundle(http, tx).await {
                     chrome_candidates.push(WebDriverLaunchCandidate {
                         driver_binary: bundle.driver_binary.to_string_lossy().to_string(),
                         browser_binary: bundle.browser_binary,
@@ -460,7 +1248,7 @@ async fn start_webdriver(
             }
             BrowserArg::Firefox => {
                 let mut firefox_candidates = Vec::new();
-                if let Ok(bundle) = ensure_geckodriver_bundle(tx).await {
+                if let Ok(bundle) = ensure_geckodriver_bundle(http, tx).await {
                     firefox_candidates.push(WebDriverLaunchCandidate {
                         driver_binary: bundle.driver_binary.to_string_lossy().to_string(),
                         browser_binary: bundle.browser_binary,
@@ -586,6 +1374,7 @@ fn configure_webdriver_command(
 
 async fn prepare_webdriver_backend(
     cli: &Cli,
+    http: &HttpOptions,
     target_url: &str,
     requested_endpoint: &str,
     tx: &UnboundedSender<CrawlEvent>,
@@ -593,7 +1382,7 @@ async fn prepare_webdriver_backend(
     let mut errors = Vec::new();
     for browser in browser_candidates(cli) {
         if matches!(browser, BrowserArg::Firefox) && !firefox_binary_available() {
-            match ensure_firefox_bundle(tx).await {
+            match ensure_firefox_bundle(http, tx).await {
                 Ok(path) => {
                     send_status(tx, format!("bundled Firefox available at {}", path.display()));
                 }
@@ -608,13 +1397,14 @@ async fn prepare_webdriver_backend(
             errors.push(format!("firefox: {msg}"));
             continue;
         }
-        match ensure_webdriver_ready(cli, browser, requested_endpoint, tx).await {
+        match ensure_webdriver_ready(cli, http, browser, requested_endpoint, tx).await {
             Ok((endpoint, child, active_browser)) => {
                 match webdriver_preflight(
                     &endpoint,
                     active_browser,
                     cli.webdriver_headless,
                     target_url,
+                    http,
                 )
                 .await
                 {
@@ -684,6 +1474,7 @@ async fn webdriver_preflight(
     browser: BrowserArg,
     headless: bool,
     target_url: &str,
+    http: &HttpOptions,
 ) -> Result<(), String> {
     let target = Url::parse(target_url).map_err(|e| format!("invalid target url: {e}"))?;
     if !matches!(target.scheme(), "http" | "https") {
@@ -692,8 +1483,8 @@ async fn webdriver_preflight(
 
     let base = endpoint.trim_end_matches('/').to_string();
     let session_endpoint = format!("{base}/session");
-    let caps = webdriver_capabilities(browser, headless);
-    let client = reqwest::Client::builder()
+    let caps = webdriver_capabilities(browser, headless, http);
+    let client = webdriver_client_builder()
         .timeout(Duration::from_secs(25))
         .build()
         .map_err(|e| format!("preflight http client build failed: {e}"))?;
@@ -780,8 +1571,8 @@ async fn webdriver_preflight(
     Ok(())
 }
 
-fn webdriver_capabilities(browser: BrowserArg, headless: bool) -> Value {
-    match browser {
+fn webdriver_capabilities(browser: BrowserArg, headless: bool, http: &HttpOptions) -> Value {
+    let mut caps = match browser {
         BrowserArg::Firefox => {
             let mut args = Vec::<String>::new();
             if headless {
@@ -872,7 +1663,11 @@ fn webdriver_capabilities(browser: BrowserArg, headless: bool) -> Value {
                 }
             })
         }
+    };
+    if let Some(proxy) = http.webdriver_proxy_capability() {
+        caps["capabilities"]["alwaysMatch"]["proxy"] = proxy;
     }
+    caps
 }
 
 fn detect_browser_binary(browser: BrowserArg) -> Option<PathBuf> {
@@ -954,71 +1749,188 @@ fn which_binary_path(name: &str) -> Option<PathBuf> {
     if p.exists() { Some(p) } else { None }
 }
 
-fn truncate_for_log(input: &str, max_chars: usize) -> String {
+pub(crate) fn truncate_for_log(input: &str, max_chars: usize) -> String {
     if input.chars().count() <= max_chars {
         return input.to_string();
     }
     input.chars().take(max_chars).collect::<String>() + "..."
 }
 
+/// Client for the WebDriver endpoint. It talks to the driver rather than the
+/// site, so it never goes through `--proxy` (or the environment's proxy); the
+/// browser gets the proxy as a capability instead.
+fn webdriver_client_builder() -> reqwest::ClientBuilder {
+    reqwest::Client::builder().no_proxy()
+}
+
+/// Client for traffic that must not carry the crawl credentials (driver and
+/// browser downloads) but should still honour `--proxy`.
+fn proxied_client_builder(http: &HttpOptions) -> reqwest::ClientBuilder {
+    let builder = reqwest::Client::builder();
+    match http
+        .proxy
+        .as_ref()
+        .and_then(|proxy| reqwest::Proxy::all(proxy.as_str()).ok())
+    {
+        Some(proxy) => {
+            builder.proxy(proxy.no_proxy(reqwest::NoProxy::from_string(&http.no_proxy.join(","))))
+        }
+        None => builder,
+    }
+}
+
+/// Client for crawl requests. Credentials are not default headers: each
+/// request adds `HttpOptions::credentials_for`, and redirects stop before
+/// leaving the credential scope.
+fn http_client_builder(http: &HttpOptions) -> reqwest::ClientBuilder {
+    proxied_client_builder(http).redirect(scoped_redirect_policy(http))
+}
+
+const MAX_REDIRECTS: usize = 10;
+
+/// Follows redirects inside the credential scope and stops at the first hop
+/// that leaves it, so credentials sent with a request never reach another
+/// host. Without credentials redirects are followed as usual.
+fn scoped_redirect_policy(http: &HttpOptions) -> reqwest::redirect::Policy {
+    if !http.is_active() {
+        return reqwest::redirect::Policy::default();
+    }
+    let scope_hosts = http.scope_hosts.clone();
+    reqwest::redirect::Policy::custom(move |attempt| {
+        if attempt.previous().len() >= MAX_REDIRECTS {
+            attempt.error("too many redirects")
+        } else if host_in_scope(attempt.url().host_str(), &scope_hosts) {
+            attempt.follow()
+        } else {
+            attempt.stop()
+        }
+    })
+}
+
+/// `scoped_redirect_policy` for spider's clients.
+fn spider_redirect_policy(http: &HttpOptions) -> spider::reqwest::redirect::Policy {
+    if !http.is_active() {
+        return spider::reqwest::redirect::Policy::default();
+    }
+    let scope_hosts = http.scope_hosts.clone();
+    spider::reqwest::redirect::Policy::custom(move |attempt| {
+        if attempt.previous().len() >= MAX_REDIRECTS {
+            attempt.error("too many redirects")
+        } else if host_in_scope(attempt.url().host_str(), &scope_hosts) {
+            attempt.follow()
+        } else {
+            attempt.stop()
+        }
+    })
+}
+
+/// spider client for fallback page fetches, without credentials; see
+/// `FetchClients` for the credentialed one.
+fn spider_client_builder(http: &HttpOptions) -> ClientBuilder {
+    let builder = ClientBuilder::new().redirect(spider_redirect_policy(http));
+    match spider_proxy(http) {
+        Some(proxy) => builder.proxy(proxy),
+        None => builder,
+    }
+}
+
+fn spider_proxy(http: &HttpOptions) -> Option<spider::reqwest::Proxy> {
+    let proxy = spider::reqwest::Proxy::all(http.proxy.as_ref()?.as_str()).ok()?;
+    Some(proxy.no_proxy(spider::reqwest::NoProxy::from_string(
+        &http.no_proxy.join(","),
+    )))
+}
+
 async fn browser_discover_and_fetch(
+    cli: &Cli,
     endpoint: &str,
     browser: BrowserArg,
-    headless: bool,
-    start_url: &str,
-    depth_limit: usize,
-    seed_sitemap: bool,
-    retries: usize,
-    retry_5xx: usize,
-    fetch_concurrency: Arc<AtomicUsize>,
-    shutdown_requested: Arc<AtomicBool>,
-    root_host: Option<&str>,
+    fetch: &FetchContext,
+    budget: &CrawlBudget,
+    added_rx: &mut UnboundedReceiver<Vec<String>>,
     tx: &UnboundedSender<CrawlEvent>,
 ) -> Result<usize, String> {
-    let client = reqwest::Client::builder()
+    let depth_limit = cli.depth.unwrap_or(0);
+    let start_urls = &cli.url;
+    let http = &fetch.http;
+    let root_hosts = fetch.root_hosts();
+    let fetch_concurrency = &fetch.concurrency;
+    let shutdown_requested = &fetch.shutdown_requested;
+    let client = webdriver_client_builder()
         .timeout(Duration::from_secs(40))
         .build()
         .map_err(|e| format!("webdriver client build failed: {e}"))?;
-    let fetch_client = ClientBuilder::new()
-        .timeout(Duration::from_secs(30))
-        .build()
-        .map_err(|e| format!("fetch client build failed: {e}"))?;
-    let redirect_client = reqwest::Client::builder()
-        .redirect(reqwest::redirect::Policy::none())
-        .connect_timeout(Duration::from_secs(10))
-        .timeout(Duration::from_secs(25))
-        .pool_max_idle_per_host(32)
+    let clients = FetchClients::build(http)?;
+    let sitemap_client = http_client_builder(http)
+        .timeout(Duration::from_secs(40))
         .build()
-        .map_err(|e| format!("redirect client build failed: {e}"))?;
+        .map_err(|e| format!("sitemap client build failed: {e}"))?;
 
-    let session_id = webdriver_create_session(&client, endpoint, browser, headless).await?;
+    let session_id =
+        webdriver_create_session(&client, endpoint, browser, cli.webdriver_headless, http).await?;
+    // Cookies can only be set for the document's current origin.
+    let first = start_urls.first().map(String::as_str).unwrap_or_default();
+    let first_host = Url::parse(first)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_ascii_lowercase))
+        .unwrap_or_default();
+    let session_cookies = http
+        .cookies
+        .iter()
+        .filter(|(domain, _, _)| cookie_domain_matches(domain, &first_host))
+        .collect::<Vec<_>>();
+    if !session_cookies.is_empty() {
+        let _ = webdriver_navigate(&client, endpoint, &session_id, &http.browser_url(first)).await;
+        for (_, name, value) in session_cookies {
+            if let Err(err) =
+                webdriver_add_cookie(&client, endpoint, &session_id, name, value).await
+            {
+                send_status(
+                    tx,
+                    format!("WebDriver cookie '{name}' not set: {}", http.redact(&err)),
+                );
+            }
+        }
+    }
+    if !http.custom_header_names.is_empty() {
+        send_status(
+            tx,
+            "WebDriver cannot send custom headers; they apply to HTTP fetches only",
+        );
+    }
     let mut discovered = HashSet::<String>::new();
     let mut visited = HashSet::<String>::new();
     let mut queue = VecDeque::<(String, usize)>::new();
-    let root_host_owned = root_host.map(|s| s.to_string());
     let mut fetch_set: JoinSet<Vec<CrawlEvent>> = JoinSet::new();
-    let start_url = normalize_crawl_url(start_url).unwrap_or_else(|| start_url.to_string());
     let mut canceled = false;
 
-    discovered.insert(start_url.clone());
-    queue.push_back((start_url.clone(), 0));
-    fetch_set.spawn(process_single_url(
-        start_url.clone(),
-        retries,
-        retry_5xx,
-        root_host_owned.clone(),
-        fetch_client.clone(),
-        redirect_client.clone(),
-    ));
+    for start_url in start_urls {
+        let start_url = normalize_crawl_url(start_url, &http.url_policy)
+            .unwrap_or_else(|| start_url.to_string());
+        if !discovered.insert(start_url.clone()) {
+            continue;
+        }
+        queue.push_back((start_url.clone(), 0));
+        fetch_set.spawn(process_single_url(
+            start_url.clone(),
+            fetch.clone(),
+            clients.clone(),
+            None,
+        ));
+    }
 
-    if seed_sitemap {
-        for url in discover_sitemap_seed_urls(&client, &start_url, root_host).await {
-            if shutdown_requested.load(Ordering::Relaxed) {
-                canceled = true;
-                break;
-            }
-            if discovered.insert(url.clone()) {
-                queue.push_back((url.clone(), 0));
+    if cli.seed_sitemap {
+        for start_url in start_urls {
+            for url in
+                discover_sitemap_seed_urls(&sitemap_client, http, start_url, root_hosts).await
+            {
+                if shutdown_requested.load(Ordering::Relaxed) {
+                    canceled = true;
+                    break;
+                }
+                if discovered.insert(url.clone()) && http.frontier.queue(&url) {
+                    queue.push_back((url.clone(), 0));
+                }
             }
         }
     }
@@ -1029,32 +1941,52 @@ async fn browser_discover_and_fetch(
             canceled = true;
             break;
         }
-        if !visited.insert(url.clone()) {
+        if budget.stopped().is_some() {
+            budget.defer(std::iter::once(url).chain(queue.drain(..).map(|(url, _)| url)));
+            break;
+        }
+        // `discovered` keeps it from being queued again.
+        http.frontier.complete(&url);
+        if http.frontier.is_excluded(&url) || !visited.insert(url.clone()) || !budget.admit(&url) {
             continue;
         }
 
-        if let Ok((redirect_rows, _)) = raw_redirect_rows(&redirect_client, &url, 8).await {
-            for (row, discovered_links) in redirect_rows {
-                let filtered_links = filter_crawlable_links(discovered_links, root_host);
-                let _ = tx.send(CrawlEvent::Page {
-                    row,
-                    discovered_links: filtered_links,
-                });
+        let mut trace_encoding = None;
+        if let Ok(trace) = raw_redirect_rows(&clients.redirect, http, &url, 8).await {
+            trace_encoding = trace
+                .content_encoding
+                .map(|encoding| (trace.final_url, encoding));
+            for (row, discovered_links) in trace.rows {
+                let filtered_links =
+                    filter_crawlable_links(discovered_links, root_hosts, &http.url_policy);
+                send_within_budget(
+                    tx,
+                    budget,
+                    &http.url_policy,
+                    CrawlEvent::Page {
+                        row: Box::new(row),
+                        discovered_links: filtered_links,
+                    },
+                );
             }
         }
 
-        if let Err(err) = webdriver_navigate(&client, endpoint, &session_id, &url).await {
+        http.pacer.wait_turn(&url).await;
+        if let Err(err) =
+            webdriver_navigate(&client, endpoint, &session_id, &http.browser_url(&url)).await
+        {
             let _ = tx.send(CrawlEvent::Error(format!(
                 "browser navigate failed for {}: {}",
-                url, err
+                url,
+                http.redact(&err)
             )));
-            while fetch_set.len() >= current_fetch_concurrency(&fetch_concurrency) {
+            while fetch_set.len() >= current_fetch_concurrency(fetch_concurrency) {
                 if shutdown_requested.load(Ordering::Relaxed) {
                     canceled = true;
                     break;
                 }
                 if let Some(joined) = fetch_set.join_next().await {
-                    emit_joined_fetch_events(joined, tx);
+                    emit_joined_fetch_events(joined, budget, &http.url_policy, tx);
                 }
             }
             if canceled {
@@ -1062,11 +1994,9 @@ async fn browser_discover_and_fetch(
             }
             fetch_set.spawn(process_single_url(
                 url.clone(),
-                retries,
-                retry_5xx,
-                root_host_owned.clone(),
-                fetch_client.clone(),
-                redirect_client.clone(),
+                fetch.clone(),
+                clients.clone(),
+                None,
             ));
             continue;
         }
@@ -1081,34 +2011,59 @@ async fn browser_discover_and_fetch(
                 Vec::new()
             }
         };
-        let filtered = filter_crawlable_links(links, root_host);
+        let filtered = filter_crawlable_links(links, root_hosts, &http.url_policy);
 
         match webdriver_rendered_snapshot(&client, endpoint, &session_id).await {
             Ok((rendered_url, rendered_html)) => {
-                let rendered_url = normalize_crawl_url(&rendered_url).unwrap_or(rendered_url);
-                let page = Page::new(&rendered_url, &fetch_client).await;
-                let (mut row, _) = page_to_row(&page, root_host);
+                let rendered_url =
+                    normalize_crawl_url(&rendered_url, &http.url_policy).unwrap_or(rendered_url);
+                http.pacer.wait_turn(&rendered_url).await;
+                let mut page = Page::new(&rendered_url, clients.page_client(&rendered_url)).await;
+                match trace_encoding.as_ref() {
+                    Some((final_url, encoding)) if *final_url == rendered_url => {
+                        set_content_encoding(&mut page, encoding);
+                    }
+                    _ if http.probe_compression && needs_content_encoding(&page) => {
+                        let credentials = http.credentials_for(&rendered_url);
+                        page = probe_content_encoding(page, clients.redirect.clone(), credentials)
+                            .await;
+                    }
+                    _ => {}
+                }
+                let (mut row, _) =
+                    page_to_row(&page, root_hosts, &http.url_policy, &http.analyzers);
                 row.url = rendered_url;
-                apply_rendered_html_to_row(&mut row, &rendered_html, root_host);
+                apply_rendered_html_to_row(
+                    &mut row,
+                    &rendered_html,
+                    root_hosts,
+                    &http.url_policy,
+                    &http.analyzers,
+                );
                 row.internal_link_count = filtered.len();
                 row.link_count = row.internal_link_count + row.external_link_count;
-                let _ = tx.send(CrawlEvent::Page {
-                    row,
-                    discovered_links: filtered.clone(),
-                });
+                send_within_budget(
+                    tx,
+                    budget,
+                    &http.url_policy,
+                    CrawlEvent::Page {
+                        row: Box::new(row),
+                        discovered_links: filtered.clone(),
+                    },
+                );
             }
             Err(err) => {
                 let _ = tx.send(CrawlEvent::Error(format!(
                     "browser rendered snapshot failed for {}: {}",
                     url, err
                 )));
-                while fetch_set.len() >= current_fetch_concurrency(&fetch_concurrency) {
+                while fetch_set.len() >= current_fetch_concurrency(fetch_concurrency) {
                     if shutdown_requested.load(Ordering::Relaxed) {
                         canceled = true;
                         break;
                     }
                     if let Some(joined) = fetch_set.join_next().await {
-                        emit_joined_fetch_events(joined, tx);
+                        emit_joined_fetch_events(joined, budget, &http.url_policy, tx);
                     }
                 }
                 if canceled {
@@ -1116,11 +2071,9 @@ async fn browser_discover_and_fetch(
                 }
                 fetch_set.spawn(process_single_url(
                     url.clone(),
-                    retries,
-                    retry_5xx,
-                    root_host_owned.clone(),
-                    fetch_client.clone(),
-                    redirect_client.clone(),
+                    fetch.clone(),
+                    clients.clone(),
+                    None,
                 ));
             }
         }
@@ -1128,15 +2081,16 @@ async fn browser_discover_and_fetch(
         for link in filtered {
             if discovered.insert(link.clone()) {
                 if unlimited_depth || depth < depth_limit {
+                    http.frontier.queue(&link);
                     queue.push_back((link, depth + 1));
                 } else {
-                    while fetch_set.len() >= current_fetch_concurrency(&fetch_concurrency) {
+                    while fetch_set.len() >= current_fetch_concurrency(fetch_concurrency) {
                         if shutdown_requested.load(Ordering::Relaxed) {
                             canceled = true;
                             break;
                         }
                         if let Some(joined) = fetch_set.join_next().await {
-                            emit_joined_fetch_events(joined, tx);
+                            emit_joined_fetch_events(joined, budget, &http.url_policy, tx);
                         }
                     }
                     if canceled {
@@ -1144,11 +2098,9 @@ async fn browser_discover_and_fetch(
                     }
                     fetch_set.spawn(process_single_url(
                         link,
-                        retries,
-                        retry_5xx,
-                        root_host_owned.clone(),
-                        fetch_client.clone(),
-                        redirect_client.clone(),
+                        fetch.clone(),
+                        clients.clone(),
+                        None,
                     ));
                 }
             }
@@ -1157,6 +2109,14 @@ async fn browser_discover_and_fetch(
             break;
         }
 
+        for url in take_added_urls(added_rx, root_hosts, http, tx) {
+            if discovered.insert(url.clone()) {
+                queue.push_back((url, 0));
+            } else {
+                http.frontier.complete(&url);
+            }
+        }
+
         if visited.len() % 10 == 0 {
             let _ = tx.send(CrawlEvent::Stats {
                 discovered: discovered.len(),
@@ -1164,7 +2124,7 @@ async fn browser_discover_and_fetch(
         }
 
         while let Some(joined) = fetch_set.try_join_next() {
-            emit_joined_fetch_events(joined, tx);
+            emit_joined_fetch_events(joined, budget, &http.url_policy, tx);
         }
     }
 
@@ -1180,7 +2140,7 @@ async fn browser_discover_and_fetch(
             canceled = true;
             fetch_set.abort_all();
         }
-        emit_joined_fetch_events(joined, tx);
+        emit_joined_fetch_events(joined, budget, &http.url_policy, tx);
         if canceled {
             while fetch_set.join_next().await.is_some() {}
             let _ = webdriver_delete_session(&client, endpoint, &session_id).await;
@@ -1193,12 +2153,14 @@ async fn browser_discover_and_fetch(
 
 fn emit_joined_fetch_events(
     joined: Result<Vec<CrawlEvent>, tokio::task::JoinError>,
+    budget: &CrawlBudget,
+    policy: &UrlPolicy,
     tx: &UnboundedSender<CrawlEvent>,
 ) {
     match joined {
         Ok(events) => {
             for event in events {
-                let _ = tx.send(event);
+                send_within_budget(tx, budget, policy, event);
             }
         }
         Err(err) => {
@@ -1212,10 +2174,11 @@ async fn webdriver_create_session(
     endpoint: &str,
     browser: BrowserArg,
     headless: bool,
+    http: &HttpOptions,
 ) -> Result<String, String> {
     let base = endpoint.trim_end_matches('/');
     let session_endpoint = format!("{base}/session");
-    let caps = webdriver_capabilities(browser, headless);
+    let caps = webdriver_capabilities(browser, headless, http);
     let res = client
         .post(&session_endpoint)
         .json(&caps)
@@ -1297,6 +2260,322 @@ async fn webdriver_navigate(
     Ok(())
 }
 
+/// Runs the `--login-script` steps in a throwaway session and copies the
+/// resulting cookies for the target host into `http`.
+async fn webdriver_login_cookies(
+    endpoint: &str,
+    browser: BrowserArg,
+    headless: bool,
+    script: &LoginScript,
+    root_hosts: Option<&[String]>,
+    http: &mut HttpOptions,
+) -> Result<usize, String> {
+    let mut field_values = Vec::with_capacity(script.fields.len());
+    for field in &script.fields {
+        let value = field.resolve_value()?;
+        http.push_secret(&value);
+        field_values.push((field.selector.as_str(), value));
+    }
+
+    let client = webdriver_client_builder()
+        .timeout(Duration::from_secs(40))
+        .build()
+        .map_err(|e| format!("webdriver client build failed: {e}"))?;
+    let session_id = webdriver_create_session(&client, endpoint, browser, headless, http).await?;
+    let result = async {
+        webdriver_navigate(
+            &client,
+            endpoint,
+            &session_id,
+            &http.browser_url(&script.url),
+        )
+        .await?;
+        let login_url = webdriver_current_url(&client, endpoint, &session_id).await?;
+        let mut last_element = None;
+        for (selector, value) in &field_values {
+            let element = webdriver_wait_for_element(
+                &client,
+                endpoint,
+                &session_id,
+                selector,
+                script.timeout_ms,
+            )
+            .await?;
+            webdriver_element_command(&client, endpoint, &session_id, &element, "clear", json!({}))
+                .await?;
+            webdriver_element_command(
+                &client,
+                endpoint,
+                &session_id,
+                &element,
+                "value",
+                json!({ "text": value }),
+            )
+            .await?;
+            last_element = Some(element);
+        }
+        match (script.submit.as_deref(), last_element) {
+            (Some(selector), _) => {
+                let button = webdriver_wait_for_element(
+                    &client,
+                    endpoint,
+                    &session_id,
+                    selector,
+                    script.timeout_ms,
+                )
+                .await?;
+                webdriver_element_command(
+                    &client,
+                    endpoint,
+                    &session_id,
+                    &button,
+                    "click",
+                    json!({}),
+                )
+                .await?;
+            }
+            // U+E007 is the WebDriver "Enter" key.
+            (None, Some(element)) => {
+                webdriver_element_command(
+                    &client,
+                    endpoint,
+                    &session_id,
+                    &element,
+                    "value",
+                    json!({ "text": "\u{E007}" }),
+                )
+                .await?;
+            }
+            (None, None) => {}
+        }
+
+        let deadline = Instant::now() + Duration::from_millis(script.timeout_ms);
+        loop {
+            let current_url = webdriver_current_url(&client, endpoint, &session_id).await?;
+            let done = match (&script.wait_for_selector, &script.wait_for_url) {
+                (None, None) => current_url != login_url,
+                (selector, url_part) => {
+                    let url_ok = url_part
+                        .as_deref()
+                        .is_none_or(|part| current_url.contains(part));
+                    let selector_ok = match selector.as_deref() {
+                        Some(selector) => {
+                            webdriver_find_element(&client, endpoint, &session_id, selector)
+                                .await
+                                .is_ok()
+                        }
+                        None => true,
+                    };
+                    url_ok && selector_ok
+                }
+            };
+            if done {
+                break;
+            }
+            if Instant::now() >= deadline {
+                if script.wait_for_selector.is_none() && script.wait_for_url.is_none() {
+                    break;
+                }
+                return Err(format!(
+                    "login wait condition not met after {} ms (at {current_url})",
+                    script.timeout_ms
+                ));
+            }
+            tokio::time::sleep(Duration::from_millis(250)).await;
+        }
+
+        webdriver_get_cookies(&client, endpoint, &session_id).await
+    }
+    .await;
+    let _ = webdriver_delete_session(&client, endpoint, &session_id).await;
+
+    let cookies = result?
+        .into_iter()
+        .filter(|(domain, _, _)| {
+            root_hosts
+                .unwrap_or_default()
+                .iter()
+                .any(|host| cookie_domain_matches(domain, host))
+        })
+        .collect::<Vec<_>>();
+    let count = cookies.len();
+    if count == 0 {
+        return Err("login finished but no cookies were set for the target host".to_string());
+    }
+    http.add_cookies(cookies)?;
+    Ok(count)
+}
+
+async fn webdriver_command(request: reqwest::RequestBuilder, label: &str) -> Result<Value, String> {
+    let res = request
+        .send()
+        .await
+        .map_err(|e| format!("{label} request failed: {e}"))?;
+    let status = res.status();
+    let body = res
+        .text()
+        .await
+        .map_err(|e| format!("{label} response read failed: {e}"))?;
+    let value: Value = serde_json::from_str(&body).unwrap_or_default();
+    if let Some(err) = value.pointer("/value/error").and_then(|v| v.as_str()) {
+        let message = value
+            .pointer("/value/message")
+            .and_then(|v| v.as_str())
+            .unwrap_or("unknown webdriver error");
+        return Err(format!("{label}: {err}: {message}"));
+    }
+    if !status.is_success() {
+        return Err(format!(
+            "{label} HTTP {}: {}",
+            status.as_u16(),
+            truncate_for_log(&body, 240)
+        ));
+    }
+    Ok(value)
+}
+
+async fn webdriver_current_url(
+    client: &reqwest::Client,
+    endpoint: &str,
+    session_id: &str,
+) -> Result<String, String> {
+    let url_endpoint = format!(
+        "{}/session/{}/url",
+        endpoint.trim_end_matches('/'),
+        session_id
+    );
+    let value = webdriver_command(client.get(url_endpoint), "current url").await?;
+    Ok(value
+        .pointer("/value")
+        .and_then(|v| v.as_str())
+        .unwrap_or_default()
+        .to_string())
+}
+
+async fn webdriver_find_element(
+    client: &reqwest::Client,
+    endpoint: &str,
+    session_id: &str,
+    selector: &str,
+) -> Result<String, String> {
+    let find_endpoint = format!(
+        "{}/session/{}/element",
+        endpoint.trim_end_matches('/'),
+        session_id
+    );
+    let request = client
+        .post(find_endpoint)
+        .json(&json!({ "using": "css selector", "value": selector }));
+    let value = webdriver_command(request, "find element").await?;
+    value
+        .pointer("/value/element-6066-11e4-a52e-4f735466cecf")
+        .or_else(|| value.pointer("/value/ELEMENT"))
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string())
+        .ok_or_else(|| format!("element '{selector}' not found"))
+}
+
+async fn webdriver_wait_for_element(
+    client: &reqwest::Client,
+    endpoint: &str,
+    session_id: &str,
+    selector: &str,
+    timeout_ms: u64,
+) -> Result<String, String> {
+    let deadline = Instant::now() + Duration::from_millis(timeout_ms);
+    loop {
+        match webdriver_find_element(client, endpoint, session_id, selector).await {
+            Ok(element) => return Ok(element),
+            Err(err) if Instant::now() >= deadline => {
+                return Err(format!("waiting for '{selector}' timed out: {err}"));
+            }
+            Err(_) => tokio::time::sleep(Duration::from_millis(250)).await,
+        }
+    }
+}
+
+async fn webdriver_element_command(
+    client: &reqwest::Client,
+    endpoint: &str,
+    session_id: &str,
+    element: &str,
+    command: &str,
+    payload: Value,
+) -> Result<(), String> {
+    let command_endpoint = format!(
+        "{}/session/{}/element/{}/{}",
+        endpoint.trim_end_matches('/'),
+        session_id,
+        element,
+        command
+    );
+    webdriver_command(client.post(command_endpoint).json(&payload), command).await?;
+    Ok(())
+}
+
+async fn webdriver_get_cookies(
+    client: &reqwest::Client,
+    endpoint: &str,
+    session_id: &str,
+) -> Result<Vec<(String, String, String)>, String> {
+    let cookie_endpoint = format!(
+        "{}/session/{}/cookie",
+        endpoint.trim_end_matches('/'),
+        session_id
+    );
+    let value = webdriver_command(client.get(cookie_endpoint), "get cookies").await?;
+    let cookies = value
+        .pointer("/value")
+        .and_then(|v| v.as_array())
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|cookie| {
+                    let name = cookie.get("name")?.as_str()?.to_string();
+                    let value = cookie.get("value")?.as_str()?.to_string();
+                    let domain = cookie
+                        .get("domain")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or_default()
+                        .to_string();
+                    Some((domain, name, value))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    Ok(cookies)
+}
+
+async fn webdriver_add_cookie(
+    client: &reqwest::Client,
+    endpoint: &str,
+    session_id: &str,
+    name: &str,
+    value: &str,
+) -> Result<(), String> {
+    let cookie_endpoint = format!(
+        "{}/session/{}/cookie",
+        endpoint.trim_end_matches('/'),
+        session_id
+    );
+    let res = client
+        .post(cookie_endpoint)
+        .json(&json!({ "cookie": { "name": name, "value": value, "path": "/" } }))
+        .send()
+        .await
+        .map_err(|e| format!("add cookie request failed: {e}"))?;
+    let status = res.status();
+    if !status.is_success() {
+        let body = res.text().await.unwrap_or_default();
+        return Err(format!(
+            "add cookie HTTP {}: {}",
+            status.as_u16(),
+            truncate_for_log(&body, 240)
+        ));
+    }
+    Ok(())
+}
+
 async fn webdriver_extract_links(
     client: &reqwest::Client,
     endpoint: &str,
@@ -1428,8 +2707,9 @@ async fn webdriver_delete_session(
 
 async fn discover_sitemap_seed_urls(
     client: &reqwest::Client,
+    http: &HttpOptions,
     start_url: &str,
-    root_host: Option<&str>,
+    root_hosts: Option<&[String]>,
 ) -> Vec<String> {
     let mut sitemap_urls = Vec::<String>::new();
     let origin = Url::parse(start_url).ok().and_then(|u| {
@@ -1445,7 +2725,12 @@ async fn discover_sitemap_seed_urls(
     let default_sitemap = format!("{origin}/sitemap.xml");
     let robots_url = format!("{origin}/robots.txt");
     let mut sitemap_sources = vec![default_sitemap];
-    if let Ok(res) = client.get(&robots_url).send().await {
+    if let Ok(res) = client
+        .get(&robots_url)
+        .headers(http.credentials_for(&robots_url))
+        .send()
+        .await
+    {
         if let Ok(text) = res.text().await {
             for line in text.lines() {
                 let trimmed = line.trim();
@@ -1464,11 +2749,16 @@ async fn discover_sitemap_seed_urls(
 
     let mut discovered = HashSet::<String>::new();
     for sitemap in sitemap_sources.into_iter().take(8) {
-        if let Ok(res) = client.get(&sitemap).send().await {
+        if let Ok(res) = client
+            .get(&sitemap)
+            .headers(http.credentials_for(&sitemap))
+            .send()
+            .await
+        {
             if let Ok(text) = res.text().await {
                 for loc in extract_xml_loc_values(&text).into_iter().take(5000) {
                     if (loc.starts_with("http://") || loc.starts_with("https://"))
-                        && is_same_host(&loc, root_host)
+                        && is_same_host(&loc, root_hosts)
                         && discovered.insert(loc.clone())
                     {
                         sitemap_urls.push(loc);
@@ -1481,7 +2771,7 @@ async fn discover_sitemap_seed_urls(
     sitemap_urls
 }
 
-fn extract_xml_loc_values(xml: &str) -> Vec<String> {
+pub(crate) fn extract_xml_loc_values(xml: &str) -> Vec<String> {
     let mut out = Vec::new();
     let mut start = 0usize;
     while let Some(open_idx) = xml[start..].find("<loc>") {
@@ -1492,7 +2782,15 @@ fn extract_xml_loc_values(xml: &str) -> Vec<String> {
         let close = open + close_rel;
         let value = xml[open..close].trim();
         if !value.is_empty() {
-            out.push(value.to_string());
+            // Sitemaps must entity-escape `&` and friends inside <loc>.
+            out.push(
+                value
+                    .replace("&lt;", "<")
+                    .replace("&gt;", ">")
+                    .replace("&quot;", "\"")
+                    .replace("&apos;", "'")
+                    .replace("&amp;", "&"),
+            );
         }
         start = close + 6;
     }
@@ -1501,6 +2799,7 @@ fn extract_xml_loc_values(xml: &str) -> Vec<String> {
 
 async fn ensure_webdriver_ready(
     cli: &Cli,
+    http: &HttpOptions,
     browser: BrowserArg,
     requested_endpoint: &str,
     tx: &UnboundedSender<CrawlEvent>,
@@ -1518,7 +2817,7 @@ async fn ensure_webdriver_ready(
 
         let mut last_err = String::new();
         for endpoint in endpoints {
-            match start_webdriver(cli, browser, &endpoint, tx).await {
+            match start_webdriver(cli, http, browser, &endpoint, tx).await {
                 Ok(child) => {
                     send_status(tx, format!("WebDriver autostarted at {}", endpoint));
                     return Ok((endpoint, Some(child), browser));
@@ -1658,6 +2957,7 @@ struct GithubAsset {
 }
 
 async fn ensure_chromedriver_bundle(
+    http: &HttpOptions,
     tx: &UnboundedSender<CrawlEvent>,
 ) -> Result<WebDriverBundle, String> {
     let platform = chromedriver_platform()
@@ -1690,7 +2990,12 @@ async fn ensure_chromedriver_bundle(
     );
 
     let manifest_url = "https://googlechromelabs.github.io/chrome-for-testing/last-known-good-versions-with-downloads.json";
-    let manifest_text = reqwest::get(manifest_url)
+    let client = proxied_client_builder(http)
+        .build()
+        .map_err(|e| format!("http client build failed: {e}"))?;
+    let manifest_text = client
+        .get(manifest_url)
+        .send()
         .await
         .map_err(|e| format!("manifest request failed: {e}"))?
         .text()
@@ -1728,7 +3033,9 @@ async fn ensure_chromedriver_bundle(
     let chrome_asset_url =
         chrome_asset_url.ok_or_else(|| format!("no chrome asset for {platform}"))?;
 
-    let driver_zip_bytes = reqwest::get(&driver_asset_url)
+    let driver_zip_bytes = client
+        .get(&driver_asset_url)
+        .send()
         .await
         .map_err(|e| format!("chromedriver download failed: {e}"))?
         .bytes()
@@ -1736,7 +3043,9 @@ async fn ensure_chromedriver_bundle(
         .map_err(|e| format!("chromedriver download read failed: {e}"))?;
     extract_binary_from_zip(&driver_zip_bytes, &driver_binary, chromedriver_leaf_name())?;
 
-    let chrome_zip_bytes = reqwest::get(&chrome_asset_url)
+    let chrome_zip_bytes = client
+        .get(&chrome_asset_url)
+        .send()
         .await
         .map_err(|e| format!("chrome download failed: {e}"))?
         .bytes()
@@ -1761,6 +3070,7 @@ async fn ensure_chromedriver_bundle(
 }
 
 async fn ensure_geckodriver_bundle(
+    http: &HttpOptions,
     tx: &UnboundedSender<CrawlEvent>,
 ) -> Result<WebDriverBundle, String> {
     let platform = geckodriver_platform()
@@ -1786,7 +3096,7 @@ async fn ensure_geckodriver_bundle(
     );
 
     let release_url = "https://api.github.com/repos/mozilla/geckodriver/releases/latest";
-    let client = reqwest::Client::builder()
+    let client = proxied_client_builder(http)
         .build()
         .map_err(|e| format!("http client build failed: {e}"))?;
     let release_text = client
@@ -1841,7 +3151,11 @@ async fn ensure_geckodriver_bundle(
     })
 }
 
-async fn ensure_firefox_bundle(tx: &UnboundedSender<CrawlEvent>) -> Result<PathBuf, String> {
+#[cfg_attr(not(target_os = "macos"), allow(unused_variables))]
+async fn ensure_firefox_bundle(
+    http: &HttpOptions,
+    tx: &UnboundedSender<CrawlEvent>,
+) -> Result<PathBuf, String> {
     let cache_dir = webdriver_cache_dir()?;
     let dist_dir = cache_dir.join("firefox-dist");
     let binary = firefox_bundle_binary_path(&dist_dir);
@@ -1855,7 +3169,7 @@ async fn ensure_firefox_bundle(tx: &UnboundedSender<CrawlEvent>) -> Result<PathB
         send_status(tx, "downloading bundled browser (Firefox)".to_string());
         fs::create_dir_all(&cache_dir).map_err(|e| format!("cache dir create failed: {e}"))?;
         let dmg_url = "https://download.mozilla.org/?product=firefox-latest-ssl&os=osx&lang=en-US";
-        let client = reqwest::Client::builder()
+        let client = proxied_client_builder(http)
             .build()
             .map_err(|e| format!("http client build failed: {e}"))?;
         let dmg_bytes = client
@@ -2163,18 +3477,23 @@ fn find_free_local_port() -> Result<u16, String> {
         .map_err(|e| format!("local addr failed: {e}"))
 }
 
-fn is_same_host(candidate: &str, root_host: Option<&str>) -> bool {
-    let Some(root) = root_host else {
+fn is_same_host(candidate: &str, root_hosts: Option<&[String]>) -> bool {
+    let Some(roots) = root_hosts else {
         return true;
     };
 
     Url::parse(candidate)
         .ok()
-        .and_then(|u| u.host_str().map(|h| h.eq_ignore_ascii_case(root)))
+        .and_then(|u| {
+            u.host_str()
+                .map(|h| roots.iter().any(|root| h.eq_ignore_ascii_case(root)))
+        })
         .unwrap_or(false)
 }
 
-fn normalize_crawl_url(raw: &str) -> Option<String> {
+/// Rewrites a URL the way the crawl's policy says. Pure: the query is only
+/// re-serialized when the policy drops or reorders parameters.
+pub(crate) fn normalize_crawl_url(raw: &str, policy: &UrlPolicy) -> Option<String> {
     let trimmed = raw.trim();
     if trimmed.is_empty() {
         return None;
@@ -2186,31 +3505,35 @@ fn normalize_crawl_url(raw: &str) -> Option<String> {
         return None;
     }
 
-    let kept_params = url
+    url.set_fragment(None);
+    let params = url
         .query_pairs()
-        .filter_map(|(k, v)| {
-            if is_tracking_query_param(&k) {
-                None
-            } else {
-                Some((k.into_owned(), v.into_owned()))
-            }
-        })
+        .map(|(k, v)| (k.into_owned(), v.into_owned()))
+        .collect::<Vec<_>>();
+    let mut kept_params = params
+        .iter()
+        .filter(|(k, _)| policy.keeps_param(k))
+        .cloned()
         .collect::<Vec<_>>();
+    if policy.sort_params {
+        kept_params.sort();
+    }
     if kept_params.is_empty() {
         url.set_query(None);
-    } else {
+    } else if kept_params != params {
         let mut serializer = url::form_urlencoded::Serializer::new(String::new());
         for (k, v) in kept_params {
             serializer.append_pair(&k, &v);
         }
         url.set_query(Some(&serializer.finish()));
     }
+    let path = policy.normalize_path(url.path());
+    url.set_path(&path);
 
-    url.set_fragment(None);
     Some(url.to_string())
 }
 
-fn is_tracking_query_param(param: &str) -> bool {
+pub(crate) fn is_tracking_query_param(param: &str) -> bool {
     let name = param.to_ascii_lowercase();
     if name.starts_with("utm_") || name.starts_with("gad_") {
         return true;
@@ -2232,18 +3555,23 @@ fn is_tracking_query_param(param: &str) -> bool {
     )
 }
 
-fn filter_crawlable_links(links: Vec<String>, root_host: Option<&str>) -> Vec<String> {
+fn filter_crawlable_links(
+    links: Vec<String>,
+    root_hosts: Option<&[String]>,
+    policy: &UrlPolicy,
+) -> Vec<String> {
     let mut out = Vec::new();
     let mut seen = HashSet::new();
 
     for link in links {
-        let Some(normalized) = normalize_crawl_url(&link) else {
+        let Some(normalized) = normalize_crawl_url(&link, policy) else {
             continue;
         };
-        if !is_same_host(&normalized, root_host) {
+        if !is_same_host(&normalized, root_hosts) {
             continue;
         }
-        if seen.insert(normalized.clone()) {
+        policy.note_link_form(&normalized, &link);
+        if seen.insert(normalized.clone()) && policy.admit(&normalized) {
             out.push(normalized);
         }
     }
@@ -2253,26 +3581,21 @@ fn filter_crawlable_links(links: Vec<String>, root_host: Option<&str>) -> Vec<St
 
 async fn fetch_missing_urls(
     urls: Vec<String>,
-    retries: usize,
-    retry_5xx: usize,
-    concurrency: Arc<AtomicUsize>,
-    shutdown_requested: Arc<AtomicBool>,
-    root_host: Option<&str>,
+    fetch: &FetchContext,
+    expectations: &HashMap<String, ListExpectation>,
+    budget: &CrawlBudget,
+    added_rx: &mut UnboundedReceiver<Vec<String>>,
     tx: &UnboundedSender<CrawlEvent>,
 ) {
-    let retries = retries.max(1);
-    if shutdown_requested.load(Ordering::Relaxed) {
+    if fetch.shutting_down() {
         return;
     }
-    let root_host_owned = root_host.map(|s| s.to_string());
-    let client = match ClientBuilder::new()
-        .timeout(Duration::from_secs(30))
-        .build()
-    {
-        Ok(c) => c,
+    let root_hosts = fetch.root_hosts();
+    let clients = match FetchClients::build(&fetch.http) {
+        Ok(clients) => clients,
         Err(err) => {
             let _ = tx.send(CrawlEvent::Error(format!(
-                "failed to create fallback client: {err}"
+                "failed to create fallback clients: {err}"
             )));
             for url in urls {
                 let _ = tx.send(CrawlEvent::Unretrieved {
@@ -2283,63 +3606,60 @@ async fn fetch_missing_urls(
             return;
         }
     };
-    let redirect_client = match reqwest::Client::builder()
-        .redirect(reqwest::redirect::Policy::none())
-        .connect_timeout(Duration::from_secs(10))
-        .timeout(Duration::from_secs(25))
-        .pool_max_idle_per_host(32)
-        .build()
-    {
-        Ok(c) => c,
-        Err(err) => {
-            let _ = tx.send(CrawlEvent::Error(format!(
-                "failed to create redirect client: {err}"
-            )));
-            for url in urls {
-                let _ = tx.send(CrawlEvent::Unretrieved {
-                    url,
-                    reason: "redirect probe client setup failed".to_string(),
-                });
-            }
-            return;
-        }
-    };
 
     let mut queue = VecDeque::new();
     let mut queued = HashSet::new();
     for url in urls {
         if queued.insert(url.clone()) {
+            fetch.http.frontier.queue(&url);
             queue.push_back(url);
         }
     }
 
     let mut set = JoinSet::new();
     loop {
-        if shutdown_requested.load(Ordering::Relaxed) {
+        if fetch.shutting_down() {
             set.abort_all();
             while set.join_next().await.is_some() {}
             break;
         }
-        while set.len() < current_fetch_concurrency(&concurrency) {
-            if shutdown_requested.load(Ordering::Relaxed) {
+        for url in take_added_urls(added_rx, root_hosts, &fetch.http, tx) {
+            if queued.insert(url.clone()) {
+                queue.push_back(url);
+            } else {
+                fetch.http.frontier.complete(&url);
+            }
+        }
+        while set.len() < current_fetch_concurrency(&fetch.concurrency) {
+            if fetch.shutting_down() {
+                break;
+            }
+            if budget.stopped().is_some() {
+                budget.defer(queue.drain(..));
                 break;
             }
             let Some(url) = queue.pop_front() else {
                 break;
             };
-            let root = root_host_owned.clone();
-            let http_client = client.clone();
-            let redir_client = redirect_client.clone();
-            set.spawn(async move {
-                process_single_url(url, retries, retry_5xx, root, http_client, redir_client).await
-            });
+            // `queued` keeps it from being queued again.
+            fetch.http.frontier.complete(&url);
+            if fetch.http.frontier.is_excluded(&url) || !budget.admit(&url) {
+                continue;
+            }
+            let expectation = expectations.get(&url).cloned();
+            set.spawn(process_single_url(
+                url,
+                fetch.clone(),
+                clients.clone(),
+                expectation,
+            ));
         }
 
         if set.is_empty() {
             break;
         }
 
-        if shutdown_requested.load(Ordering::Relaxed) {
+        if fetch.shutting_down() {
             set.abort_all();
             while set.join_next().await.is_some() {}
             break;
@@ -2349,7 +3669,7 @@ async fn fetch_missing_urls(
             match joined {
                 Ok(events) => {
                     for event in events {
-                        let _ = tx.send(event);
+                        send_within_budget(tx, budget, &fetch.http.url_policy, event);
                     }
                 }
                 Err(err) => {
@@ -2360,39 +3680,297 @@ async fn fetch_missing_urls(
     }
 }
 
+/// Re-crawls from the seeds and every URL of a previous export. Known pages
+/// with an ETag or Last-Modified are revalidated with a conditional GET; a
+/// 304 carries the previous row forward as `unchanged`, a 200 is parsed from
+/// the same response, and pages without validators are fetched in full. Rows
+/// that are not unchanged are marked `changed` or `new`.
+async fn incremental_crawl(
+    seeds: &[String],
+    previous: Vec<(CrawlRow, Vec<String>)>,
+    depth_limit: usize,
+    fetch: &FetchContext,
+    budget: &CrawlBudget,
+    added_rx: &mut UnboundedReceiver<Vec<String>>,
+    tx: &UnboundedSender<CrawlEvent>,
+) {
+    let root_hosts = fetch.root_hosts();
+    let clients = match FetchClients::build(&fetch.http) {
+        Ok(clients) => clients,
+        Err(err) => {
+            let _ = tx.send(CrawlEvent::Error(format!(
+                "failed to create fetch clients: {err}"
+            )));
+            return;
+        }
+    };
+
+    let mut previous = previous
+        .into_iter()
+        .map(|(row, links)| {
+            let url = normalize_crawl_url(&row.url, &fetch.http.url_policy)
+                .unwrap_or_else(|| row.url.clone());
+            (url, (row, links))
+        })
+        .filter(|(url, _)| is_same_host(url, root_hosts))
+        .collect::<HashMap<_, _>>();
+    let previous_urls = previous.keys().cloned().collect::<HashSet<_>>();
+    let mut queue = VecDeque::<(String, usize)>::new();
+    let mut queued = HashSet::<String>::new();
+    let mut known = previous_urls.iter().cloned().collect::<Vec<_>>();
+    known.sort();
+    for url in seeds
+        .iter()
+        .filter_map(|url| normalize_crawl_url(url, &fetch.http.url_policy))
+        .chain(known)
+    {
+        if queued.insert(url.clone()) {
+            fetch.http.frontier.queue(&url);
+            queue.push_back((url, 0));
+        }
+    }
+    let _ = tx.send(CrawlEvent::Stats {
+        discovered: queued.len(),
+    });
+
+    let unlimited_depth = depth_limit == 0;
+    let (mut unchanged, mut changed, mut new) = (0usize, 0usize, 0usize);
+    let mut set = JoinSet::new();
+    loop {
+        if fetch.shutting_down() {
+            set.abort_all();
+            while set.join_next().await.is_some() {}
+            return;
+        }
+        for url in take_added_urls(added_rx, root_hosts, &fetch.http, tx) {
+            if queued.insert(url.clone()) {
+                queue.push_back((url, 0));
+            } else {
+                fetch.http.frontier.complete(&url);
+            }
+        }
+        while set.len() < current_fetch_concurrency(&fetch.concurrency) {
+            if budget.stopped().is_some() {
+                budget.defer(queue.drain(..).map(|(url, _)| url));
+                break;
+            }
+            let Some((url, depth)) = queue.pop_front() else {
+                break;
+            };
+            // `queued` keeps it from being queued again.
+            fetch.http.frontier.complete(&url);
+            if fetch.http.frontier.is_excluded(&url) || !budget.admit(&url) {
+                continue;
+            }
+            let prior = previous.remove(&url);
+            let fetch = fetch.clone();
+            let clients = clients.clone();
+            set.spawn(async move {
+                let events = process_conditional_url(url, prior, fetch, clients).await;
+                (depth, events)
+            });
+        }
+
+        let Some(joined) = set.join_next().await else {
+            break;
+        };
+        let (depth, events) = match joined {
+            Ok(result) => result,
+            Err(err) => {
+                let _ = tx.send(CrawlEvent::Error(format!("fetch worker failed: {err}")));
+                continue;
+            }
+        };
+        for mut event in events {
+            if let CrawlEvent::Page {
+                row,
+                discovered_links,
+            } = &mut event
+            {
+                if row.change_status.is_empty() {
+                    row.change_status = if previous_urls.contains(&row.url) {
+                        "changed".to_string()
+                    } else {
+                        "new".to_string()
+                    };
+                }
+                match row.change_status.as_str() {
+                    "unchanged" => unchanged += 1,
+                    "changed" => changed += 1,
+                    _ => new += 1,
+                }
+                if unlimited_depth || depth < depth_limit {
+                    for link in discovered_links.iter() {
+                        if queued.insert(link.clone()) {
+                            fetch.http.frontier.queue(link);
+                            queue.push_back((link.clone(), depth + 1));
+                        }
+                    }
+                }
+            }
+            send_within_budget(tx, budget, &fetch.http.url_policy, event);
+        }
+        let _ = tx.send(CrawlEvent::Stats {
+            discovered: queued.len(),
+        });
+    }
+
+    send_status(
+        tx,
+        format!("Incremental crawl: {unchanged} unchanged, {changed} changed, {new} new pages"),
+    );
+}
+
+async fn process_conditional_url(
+    url: String,
+    previous: Option<(CrawlRow, Vec<String>)>,
+    fetch: FetchContext,
+    clients: FetchClients,
+) -> Vec<CrawlEvent> {
+    let pacer = &fetch.http.pacer;
+    if let Some((previous_row, previous_links)) = previous
+        && (200..=299).contains(&previous_row.status)
+        && (!previous_row.etag.is_empty() || !previous_row.last_modified.is_empty())
+    {
+        let mut request = clients.page_client(&url).get(&url);
+        if !previous_row.etag.is_empty() {
+            request = request.header(
+                spider::reqwest::header::IF_NONE_MATCH,
+                previous_row.etag.as_str(),
+            );
+        }
+        if !previous_row.last_modified.is_empty() {
+            request = request.header(
+                spider::reqwest::header::IF_MODIFIED_SINCE,
+                previous_row.last_modified.as_str(),
+            );
+        }
+        pacer.wait_turn(&url).await;
+        let started = Instant::now();
+        if let Ok(response) = request.send().await {
+            let status = response.status();
+            let headers = response.headers().clone();
+            pacer.observe(
+                &url,
+                status.as_u16(),
+                headers
+                    .get(spider::reqwest::header::RETRY_AFTER)
+                    .and_then(|v| v.to_str().ok()),
+            );
+            if status.as_u16() == 304 {
+                let mut row = previous_row;
+                row.change_status = "unchanged".to_string();
+                row.response_time = started.elapsed().as_millis();
+                row.crawl_timestamp = Utc::now().to_rfc3339();
+                let discovered_links = filter_crawlable_links(
+                    previous_links,
+                    fetch.root_hosts(),
+                    &fetch.http.url_policy,
+                );
+                return vec![CrawlEvent::Page {
+                    row: Box::new(row),
+                    discovered_links,
+                }];
+            }
+            // A changed page is parsed from this response; redirects and
+            // errors go through the full fetch with its hop rows and retries.
+            let final_url = response.url().to_string();
+            if status.is_success()
+                && normalize_crawl_url(&final_url, &fetch.http.url_policy).as_deref()
+                    == Some(url.as_str())
+                && let Ok(body) = response.bytes().await
+            {
+                let mut page = spider::page::build(
+                    &url,
+                    spider::utils::PageResponse {
+                        content: Some(body.to_vec()),
+                        headers: Some(headers),
+                        status_code: status,
+                        final_url: Some(final_url),
+                        ..Default::default()
+                    },
+                );
+                page.set_duration_elapsed_from_duration(Some(started.elapsed()));
+                if fetch.http.probe_compression && needs_content_encoding(&page) {
+                    let credentials = fetch.http.credentials_for(&url);
+                    page =
+                        probe_content_encoding(page, clients.redirect.clone(), credentials).await;
+                }
+                let (mut row, discovered_links) = page_to_row(
+                    &page,
+                    fetch.root_hosts(),
+                    &fetch.http.url_policy,
+                    &fetch.http.analyzers,
+                );
+                row.url = url;
+                let discovered_links = filter_crawlable_links(
+                    discovered_links,
+                    fetch.root_hosts(),
+                    &fetch.http.url_policy,
+                );
+                row.link_count = discovered_links.len();
+                return vec![CrawlEvent::Page {
+                    row: Box::new(row),
+                    discovered_links,
+                }];
+            }
+        }
+    }
+
+    process_single_url(url, fetch, clients, None).await
+}
+
 async fn process_single_url(
     url: String,
-    retries: usize,
-    retry_5xx: usize,
-    root_host: Option<String>,
-    client: spider::Client,
-    redirect_client: reqwest::Client,
+    fetch: FetchContext,
+    clients: FetchClients,
+    expectation: Option<ListExpectation>,
 ) -> Vec<CrawlEvent> {
     let mut out = Vec::new();
-    let root_host_ref = root_host.as_deref();
-
-    let (redirect_rows, fetch_url) = match raw_redirect_rows(&redirect_client, &url, 8).await {
-        Ok(v) => v,
-        Err(_) => (Vec::new(), url.clone()),
+    let root_hosts_ref = fetch.root_hosts();
+    let pacer = &fetch.http.pacer;
+
+    let trace = match raw_redirect_rows(&clients.redirect, &fetch.http, &url, 8).await {
+        Ok(trace) => trace,
+        Err(_) => RedirectTrace {
+            rows: Vec::new(),
+            final_url: url.clone(),
+            final_status: None,
+            content_encoding: None,
+            stop: RedirectStop::RequestFailed,
+        },
     };
+    let fetch_url = trace.final_url;
+    let client = clients.page_client(&fetch_url);
 
-    for (row, discovered_links) in redirect_rows {
+    for (row, discovered_links) in trace.rows {
         out.push(CrawlEvent::Page {
-            row,
+            row: Box::new(row),
             discovered_links,
         });
     }
 
-    let retries = retries.max(1);
+    let retries = fetch.retries.max(1);
+    let retry_5xx = fetch.retry_5xx;
     let mut last_page: Option<Page> = None;
     let mut last_status = 0u16;
 
     for queue_round in 0..=retry_5xx {
         for attempt in 0..retries {
-            let page = Page::new(&fetch_url, &client).await;
+            if attempt > 0 || queue_round > 0 {
+                pacer.record_retry(&fetch_url);
+            }
+            pacer.wait_turn(&fetch_url).await;
+            let page = Page::new(&fetch_url, client).await;
             last_status = page.status_code.as_u16();
+            // A 429/503 blocks the host until Retry-After; the next wait_turn honours it.
+            pacer.observe(
+                &fetch_url,
+                last_status,
+                header_value(&page, "retry-after").as_deref(),
+            );
             last_page = Some(page);
-            if last_status < 500 {
+            if last_status < 500 && last_status != 429 {
                 break;
             }
             if attempt + 1 < retries {
@@ -2400,17 +3978,26 @@ async fn process_single_url(
             }
         }
 
-        if last_status < 500 || queue_round == retry_5xx {
+        if (last_status < 500 && last_status != 429) || queue_round == retry_5xx {
             break;
         }
 
         tokio::time::sleep(Duration::from_millis(350 * (queue_round as u64 + 1))).await;
     }
 
-    if let Some(page) = last_page {
-        let (mut row, discovered_links) = page_to_row(&page, root_host_ref);
+    if let Some(mut page) = last_page {
+        if let Some(encoding) = trace.content_encoding.as_deref() {
+            set_content_encoding(&mut page, encoding);
+        }
+        let (mut row, discovered_links) = page_to_row(
+            &page,
+            root_hosts_ref,
+            &fetch.http.url_policy,
+            &fetch.http.analyzers,
+        );
         row.url = fetch_url.clone();
-        let filtered_links = filter_crawlable_links(discovered_links, root_host_ref);
+        let filtered_links =
+            filter_crawlable_links(discovered_links, root_hosts_ref, &fetch.http.url_policy);
         row.link_count = filtered_links.len();
         if row.size == 0 && row.status >= 500 {
             out.push(CrawlEvent::Unretrieved {
@@ -2422,7 +4009,7 @@ async fn process_single_url(
             });
         } else {
             out.push(CrawlEvent::Page {
-                row,
+                row: Box::new(row),
                 discovered_links: filtered_links,
             });
         }
@@ -2433,33 +4020,269 @@ async fn process_single_url(
         });
     }
 
+    if let Some(expectation) = expectation.as_ref() {
+        apply_list_expectation(
+            &url,
+            expectation,
+            &fetch_url,
+            &fetch.http.url_policy,
+            &mut out,
+        );
+    }
+
     out
 }
 
+/// Follows every old URL of a redirect mapping hop by hop and classifies the
+/// result against the expected destination.
+pub(crate) async fn verify_redirect_mapping(
+    entries: Vec<(String, ListExpectation)>,
+    concurrency: usize,
+    http: &HttpOptions,
+) -> Result<Vec<RedirectCheck>, String> {
+    let client = http_client_builder(http)
+        .redirect(reqwest::redirect::Policy::none())
+        .connect_timeout(Duration::from_secs(10))
+        .timeout(Duration::from_secs(25))
+        .build()
+        .map_err(|e| format!("redirect client build failed: {e}"))?;
+
+    let mut queue = entries.into_iter().enumerate().collect::<VecDeque<_>>();
+    let mut checks = Vec::with_capacity(queue.len());
+    let mut set = JoinSet::new();
+    loop {
+        while set.len() < sanitize_fetch_concurrency(concurrency) {
+            let Some((index, (old_url, expectation))) = queue.pop_front() else {
+                break;
+            };
+            let client = client.clone();
+            let http = http.clone();
+            set.spawn(async move {
+                (
+                    index,
+                    check_redirect_mapping(&client, &http, &old_url, &expectation).await,
+                )
+            });
+        }
+        let Some(joined) = set.join_next().await else {
+            break;
+        };
+        match joined {
+            Ok(check) => checks.push(check),
+            Err(err) => return Err(format!("redirect check worker failed: {err}")),
+        }
+    }
+    checks.sort_by_key(|(index, _)| *index);
+    Ok(checks.into_iter().map(|(_, check)| check).collect())
+}
+
+async fn check_redirect_mapping(
+    client: &reqwest::Client,
+    http: &HttpOptions,
+    old_url: &str,
+    expectation: &ListExpectation,
+) -> RedirectCheck {
+    let old_url =
+        normalize_crawl_url(old_url, &http.url_policy).unwrap_or_else(|| old_url.to_string());
+    let expected_url = expectation
+        .redirect_target
+        .as_deref()
+        .map(|target| {
+            let resolved = Url::parse(&old_url)
+                .ok()
+                .and_then(|base| base.join(target).ok())
+                .map(|u| u.to_string())
+                .unwrap_or_else(|| target.to_string());
+            normalize_crawl_url(&resolved, &http.url_policy).unwrap_or(resolved)
+        })
+        .unwrap_or_default();
+
+    let (rows, final_url, final_status, stop) = raw_redirect_rows(client, http, &old_url, 10)
+        .await
+        .map(|trace| (trace.rows, trace.final_url, trace.final_status, trace.stop))
+        .unwrap_or_else(|_| {
+            (
+                Vec::new(),
+                old_url.clone(),
+                None,
+                RedirectStop::RequestFailed,
+            )
+        });
+    let hops = rows
+        .iter()
+        .map(|(row, _)| (row.status, row.redirect_url.clone()))
+        .collect::<Vec<_>>();
+
+    let outcome = match stop {
+        RedirectStop::RequestFailed => RedirectOutcome::RequestFailed,
+        RedirectStop::Revisited => RedirectOutcome::RedirectLoop,
+        RedirectStop::HopLimit => RedirectOutcome::TooManyRedirects,
+        RedirectStop::MissingLocation => RedirectOutcome::MissingLocation,
+        RedirectStop::Final if final_status.is_some_and(|status| status >= 400) => {
+            RedirectOutcome::BrokenTarget
+        }
+        RedirectStop::Final if hops.is_empty() => RedirectOutcome::NotRedirected,
+        RedirectStop::Final if !expected_url.is_empty() && final_url != expected_url => {
+            RedirectOutcome::WrongTarget
+        }
+        RedirectStop::Final
+            if expectation
+                .status
+                .is_some_and(|expected| hops[0].0 != expected) =>
+        {
+            RedirectOutcome::UnexpectedStatus
+        }
+        RedirectStop::Final if hops.iter().any(|(status, _)| !matches!(status, 301 | 308)) => {
+            RedirectOutcome::Temporary
+        }
+        RedirectStop::Final if hops.len() > 1 => RedirectOutcome::Chain,
+        RedirectStop::Final => RedirectOutcome::Match,
+    };
+
+    RedirectCheck {
+        old_url,
+        expected_url,
+        final_url,
+        final_status,
+        hops,
+        outcome,
+    }
+}
+
+/// Checks a `--list` entry against its expected first-hop status and final
+/// redirect destination, flagging the entry's own row on mismatch.
+fn apply_list_expectation(
+    url: &str,
+    expectation: &ListExpectation,
+    final_url: &str,
+    policy: &UrlPolicy,
+    events: &mut Vec<CrawlEvent>,
+) {
+    let entry_url = normalize_crawl_url(url, policy).unwrap_or_else(|| url.to_string());
+    let mut failures = Vec::new();
+    let row = events.iter_mut().find_map(|event| match event {
+        CrawlEvent::Page { row, .. } if row.url == entry_url => Some(row),
+        _ => None,
+    });
+
+    let actual_status = row.as_ref().map(|row| row.status);
+    if let Some(expected) = expectation.status
+        && actual_status != Some(expected)
+    {
+        let actual = actual_status.map_or_else(|| "no response".to_string(), |s| s.to_string());
+        failures.push((
+            SeoIssue::UnexpectedStatus,
+            format!("expected status {expected}, got {actual}"),
+        ));
+    }
+    if let Some(target) = expectation.redirect_target.as_deref() {
+        let expected = Url::parse(&entry_url)
+            .ok()
+            .and_then(|base| base.join(target).ok())
+            .map(|u| u.to_string())
+            .unwrap_or_else(|| target.to_string());
+        let expected = normalize_crawl_url(&expected, policy).unwrap_or(expected);
+        let actual =
+            normalize_crawl_url(final_url, policy).unwrap_or_else(|| final_url.to_string());
+        if actual != expected {
+            failures.push((
+                SeoIssue::UnexpectedRedirectTarget,
+                format!("expected redirect to {expected}, ended at {actual}"),
+            ));
+        }
+    }
+
+    if let Some(row) = row {
+        for (issue, _) in &failures {
+            row.issues.push(issue.clone());
+        }
+        row.seo_score = compute_seo_score(&row.issues);
+    }
+    for (_, message) in failures {
+        events.push(CrawlEvent::Error(format!(
+            "list check failed for {entry_url}: {message}"
+        )));
+    }
+}
+
+/// The hops `raw_redirect_rows` followed and what the final response said.
+struct RedirectTrace {
+    rows: Vec<(CrawlRow, Vec<String>)>,
+    final_url: String,
+    /// The status `final_url` answered with; `None` when it was never
+    /// requested (hop limit) or its request failed.
+    final_status: Option<u16>,
+    /// Content-Encoding of a 2xx final response (`identity` when it was not
+    /// compressed); the client does not decode bodies, so it is the server's.
+    content_encoding: Option<String>,
+    stop: RedirectStop,
+}
+
+/// Why `raw_redirect_rows` stopped following hops.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RedirectStop {
+    /// A response that is not a redirect.
+    Final,
+    /// The next hop was a URL already visited.
+    Revisited,
+    /// Still redirecting after `max_hops` hops.
+    HopLimit,
+    /// A 3xx response without a Location header.
+    MissingLocation,
+    RequestFailed,
+}
+
 async fn raw_redirect_rows(
     client: &reqwest::Client,
+    http: &HttpOptions,
     start_url: &str,
     max_hops: usize,
-) -> Result<(Vec<(CrawlRow, Vec<String>)>, String), String> {
+) -> Result<RedirectTrace, String> {
     let mut rows = Vec::<(CrawlRow, Vec<String>)>::new();
-    let mut current = normalize_crawl_url(start_url).unwrap_or_else(|| start_url.to_string());
+    let mut current =
+        normalize_crawl_url(start_url, &http.url_policy).unwrap_or_else(|| start_url.to_string());
     let mut seen = HashSet::<String>::new();
+    let mut content_encoding = None;
+    let mut final_status = None;
+    let mut stop = RedirectStop::HopLimit;
 
     for _ in 0..max_hops.max(1) {
         if !seen.insert(current.clone()) {
+            stop = RedirectStop::Revisited;
             break;
         }
 
+        http.pacer.wait_turn(&current).await;
         let started = Instant::now();
-        let response = match send_redirect_probe_request(client, &current, 3).await {
+        let response = match send_redirect_probe_request(client, http, &current, 3).await {
             Ok(response) => response,
-            Err(_) => break,
+            Err(_) => {
+                stop = RedirectStop::RequestFailed;
+                break;
+            }
         };
         let elapsed = started.elapsed().as_millis();
         let status = response.status().as_u16();
         let headers = response.headers().clone();
+        http.pacer.observe(
+            &current,
+            status,
+            headers
+                .get(reqwest::header::RETRY_AFTER)
+                .and_then(|v| v.to_str().ok()),
+        );
 
         if !(300..=399).contains(&status) {
+            if (200..=299).contains(&status) {
+                let encoding = response_header(&headers, "content-encoding");
+                content_encoding = Some(if encoding.is_empty() {
+                    "identity".to_string()
+                } else {
+                    encoding
+                });
+            }
+            final_status = Some(status);
+            stop = RedirectStop::Final;
             break;
         }
 
@@ -2469,6 +4292,8 @@ async fn raw_redirect_rows(
             .map(|v| v.trim().to_string())
             .unwrap_or_default();
         if location_raw.is_empty() {
+            final_status = Some(status);
+            stop = RedirectStop::MissingLocation;
             break;
         }
 
@@ -2477,7 +4302,8 @@ async fn raw_redirect_rows(
             .and_then(|base| base.join(&location_raw).ok())
             .map(|u| u.to_string())
             .unwrap_or(location_raw.clone());
-        let resolved_target = normalize_crawl_url(&resolved_target).unwrap_or(resolved_target);
+        let resolved_target =
+            normalize_crawl_url(&resolved_target, &http.url_policy).unwrap_or(resolved_target);
         let mime = headers
             .get(reqwest::header::CONTENT_TYPE)
             .and_then(|v| v.to_str().ok())
@@ -2516,6 +4342,21 @@ async fn raw_redirect_rows(
             image_count: 0,
             image_missing_alt_count: 0,
             structured_data_count: 0,
+            mixed_content_count: 0,
+            insecure_link_count: 0,
+            strict_transport_security: response_header(&headers, "strict-transport-security"),
+            content_security_policy: response_header(&headers, "content-security-policy"),
+            x_content_type_options: response_header(&headers, "x-content-type-options"),
+            x_frame_options: response_header(&headers, "x-frame-options"),
+            referrer_policy: response_header(&headers, "referrer-policy"),
+            cache_control: response_header(&headers, "cache-control"),
+            etag: response_header(&headers, "etag"),
+            vary: response_header(&headers, "vary"),
+            content_encoding: response_header(&headers, "content-encoding"),
+            change_status: String::new(),
+            pagination_next: String::new(),
+            pagination_prev: String::new(),
+            extra_fields: BTreeMap::new(),
             seo_score: 100,
             issues: Vec::new(),
             crawl_timestamp: Utc::now().to_rfc3339(),
@@ -2523,12 +4364,28 @@ async fn raw_redirect_rows(
         rows.push((row, vec![resolved_target.clone()]));
         current = resolved_target;
     }
+    if stop == RedirectStop::HopLimit && seen.contains(&current) {
+        stop = RedirectStop::Revisited;
+    }
+    if stop == RedirectStop::Revisited {
+        final_status = rows
+            .iter()
+            .find(|(row, _)| row.url == current)
+            .map(|(row, _)| row.status);
+    }
 
-    Ok((rows, current))
+    Ok(RedirectTrace {
+        rows,
+        final_url: current,
+        final_status,
+        content_encoding,
+        stop,
+    })
 }
 
 async fn send_redirect_probe_request(
     client: &reqwest::Client,
+    http: &HttpOptions,
     url: &str,
     attempts: usize,
 ) -> Result<reqwest::Response, String> {
@@ -2536,7 +4393,13 @@ async fn send_redirect_probe_request(
     let max_attempts = attempts.max(1);
 
     for attempt in 1..=max_attempts {
-        match client.get(url).send().await {
+        match client
+            .get(url)
+            .headers(http.credentials_for(url))
+            .header(reqwest::header::ACCEPT_ENCODING, "gzip, deflate, br")
+            .send()
+            .await
+        {
             Ok(response) => return Ok(response),
             Err(err) => {
                 last_error = err.to_string();
@@ -2552,7 +4415,12 @@ async fn send_redirect_probe_request(
     Err(last_error)
 }
 
-fn page_to_row(page: &spider::page::Page, root_host: Option<&str>) -> (CrawlRow, Vec<String>) {
+fn page_to_row(
+    page: &spider::page::Page,
+    root_hosts: Option<&[String]>,
+    policy: &UrlPolicy,
+    analyzers: &AnalyzerRegistry,
+) -> (CrawlRow, Vec<String>) {
     let html = page.get_html();
     let doc = Html::parse_document(&html);
 
@@ -2567,9 +4435,10 @@ fn page_to_row(page: &spider::page::Page, root_host: Option<&str>) -> (CrawlRow,
 
     let requested_url_raw = page.get_url().to_string();
     let final_url_raw = page.get_url_final().to_string();
-    let requested_url =
-        normalize_crawl_url(&requested_url_raw).unwrap_or_else(|| requested_url_raw.clone());
-    let final_url = normalize_crawl_url(&final_url_raw).unwrap_or_else(|| final_url_raw.clone());
+    let requested_url = normalize_crawl_url(&requested_url_raw, policy)
+        .unwrap_or_else(|| requested_url_raw.clone());
+    let final_url =
+        normalize_crawl_url(&final_url_raw, policy).unwrap_or_else(|| final_url_raw.clone());
     let is_followed_redirect = requested_url != final_url;
     let row_url = if (300..=399).contains(&status) {
         requested_url.clone()
@@ -2582,7 +4451,14 @@ fn page_to_row(page: &spider::page::Page, root_host: Option<&str>) -> (CrawlRow,
         if is_followed_redirect {
             final_url.clone()
         } else {
-            String::new()
+            // Not followed, e.g. stopped at the edge of the credential scope.
+            header_value(page, "location")
+                .and_then(|location| Url::parse(&requested_url).ok()?.join(&location).ok())
+                .map(|target| {
+                    normalize_crawl_url(target.as_str(), policy)
+                        .unwrap_or_else(|| target.to_string())
+                })
+                .unwrap_or_default()
         }
     } else {
         String::new()
@@ -2596,7 +4472,7 @@ fn page_to_row(page: &spider::page::Page, root_host: Option<&str>) -> (CrawlRow,
     if title.is_empty() {
         title = h1.clone();
     }
-    let canonical = extract_canonical(&doc, &row_url);
+    let canonical = extract_canonical(&doc, &row_url, policy);
     let noindex = has_noindex(&doc, page);
     let is_html = mime.to_ascii_lowercase().contains("html");
     let h1_count = if is_html {
@@ -2620,11 +4496,27 @@ fn page_to_row(page: &spider::page::Page, root_host: Option<&str>) -> (CrawlRow,
         0
     };
     let word_count = if is_html { count_words(&doc) } else { 0 };
+    let (pagination_next, pagination_prev) = if is_html {
+        extract_pagination_links(&doc, &row_url, policy)
+    } else {
+        (String::new(), String::new())
+    };
     let (doc_links, internal_link_count, external_link_count) = if is_html {
-        extract_crawl_links_with_breakdown(&doc, &row_url, root_host)
+        extract_crawl_links_with_breakdown(&doc, &row_url, root_hosts)
     } else {
         (Vec::new(), 0, 0)
     };
+    let (mixed_content_count, insecure_link_count) = if is_html {
+        (
+            count_mixed_content(&doc, &row_url),
+            count_insecure_internal_links(&doc, &row_url, root_hosts),
+        )
+    } else {
+        (0, 0)
+    };
+
+    let strict_transport_security =
+        header_value(page, "strict-transport-security").unwrap_or_default();
 
     let size = page.get_html_bytes_u8().len();
     let response_time = page.get_duration_elapsed().as_millis();
@@ -2639,75 +4531,116 @@ fn page_to_row(page: &spider::page::Page, root_host: Option<&str>) -> (CrawlRow,
     let mut discovered_dedupe = HashSet::new();
     discovered_links.retain(|link| discovered_dedupe.insert(link.clone()));
 
-    let issues = collect_row_issues(
-        status,
-        "retrieved",
-        is_html,
-        noindex,
-        title.chars().count(),
-        meta.chars().count(),
-        h1_count,
-        &canonical,
-        word_count,
-        image_missing_alt_count,
-        external_link_count,
-    );
-    let seo_score = compute_seo_score(&issues);
     let indexability = if (200..=299).contains(&status) && !noindex {
         "Indexable".to_string()
     } else {
         "Non-Indexable".to_string()
     };
 
-    (
-        CrawlRow {
-            url: row_url,
-            status,
-            mime,
-            retrieval_status: "retrieved".to_string(),
-            indexability,
-            title_length: title.chars().count(),
-            title,
-            meta_length: meta.chars().count(),
-            meta,
-            h1,
-            canonical,
-            word_count,
-            size,
-            response_time,
-            last_modified,
-            redirect_url,
-            redirect_type,
-            link_count: discovered_links.len(),
-            internal_link_count,
-            external_link_count,
-            h1_count,
-            h2_count,
-            image_count,
-            image_missing_alt_count,
-            structured_data_count,
-            seo_score,
-            issues,
-            crawl_timestamp: Utc::now().to_rfc3339(),
-        },
-        discovered_links,
-    )
+    let mut row = CrawlRow {
+        url: row_url,
+        status,
+        mime,
+        retrieval_status: "retrieved".to_string(),
+        indexability,
+        title_length: title.chars().count(),
+        title,
+        meta_length: meta.chars().count(),
+        meta,
+        h1,
+        canonical,
+        word_count,
+        size,
+        response_time,
+        last_modified,
+        redirect_url,
+        redirect_type,
+        link_count: discovered_links.len(),
+        internal_link_count,
+        external_link_count,
+        h1_count,
+        h2_count,
+        image_count,
+        image_missing_alt_count,
+        structured_data_count,
+        mixed_content_count,
+        insecure_link_count,
+        strict_transport_security,
+        content_security_policy: header_value(page, "content-security-policy").unwrap_or_default(),
+        x_content_type_options: header_value(page, "x-content-type-options").unwrap_or_default(),
+        x_frame_options: header_value(page, "x-frame-options").unwrap_or_default(),
+        referrer_policy: header_value(page, "referrer-policy").unwrap_or_default(),
+        cache_control: header_value(page, "cache-control").unwrap_or_default(),
+        etag: header_value(page, "etag").unwrap_or_default(),
+        vary: header_value(page, "vary").unwrap_or_default(),
+        content_encoding: header_value(page, "content-encoding").unwrap_or_default(),
+        change_status: String::new(),
+        pagination_next,
+        pagination_prev,
+        extra_fields: BTreeMap::new(),
+        seo_score: 0,
+        issues: Vec::new(),
+        crawl_timestamp: Utc::now().to_rfc3339(),
+    };
+    let headers = page
+        .headers
+        .iter()
+        .flatten()
+        .filter_map(|(name, value)| {
+            value
+                .to_str()
+                .ok()
+                .map(|value| (name.as_str().to_string(), value.to_string()))
+        })
+        .collect::<Vec<_>>();
+    // Spider fetches links already normalized; fall back to a linked form.
+    let raw_url = if row.url == final_url {
+        &final_url_raw
+    } else {
+        &requested_url_raw
+    };
+    let requested_as = if *raw_url == row.url {
+        policy
+            .linked_form(&row.url)
+            .unwrap_or_else(|| raw_url.clone())
+    } else {
+        raw_url.clone()
+    };
+    analyze_row(
+        analyzers,
+        &mut row,
+        &requested_as,
+        is_html.then_some(&doc),
+        None,
+        &headers,
+    );
+
+    (row, discovered_links)
 }
 
-fn apply_rendered_html_to_row(row: &mut CrawlRow, html: &str, root_host: Option<&str>) {
+pub(crate) fn apply_rendered_html_to_row(
+    row: &mut CrawlRow,
+    html: &str,
+    root_hosts: Option<&[String]>,
+    policy: &UrlPolicy,
+    analyzers: &AnalyzerRegistry,
+) {
     let doc = Html::parse_document(html);
     let title = extract_title(&doc);
     let meta = extract_meta_description(&doc);
     let h1 = extract_real_h1(&doc);
-    let canonical = extract_canonical(&doc, &row.url);
+    let canonical = extract_canonical(&doc, &row.url, policy);
     let noindex = has_noindex_meta(&doc);
     let h1_count = count_elements(&doc, "h1");
     let h2_count = count_elements(&doc, "h2");
     let (image_count, image_missing_alt_count) = image_alt_stats(&doc);
     let structured_data_count = count_structured_data_blocks(&doc);
     let (_, internal_link_count, external_link_count) =
-        extract_crawl_links_with_breakdown(&doc, &row.url, root_host);
+        extract_crawl_links_with_breakdown(&doc, &row.url, root_hosts);
     let word_count = count_words(&doc);
+    let mixed_content_count = count_mixed_content(&doc, &row.url);
+    let insecure_link_count = count_insecure_internal_links(&doc, &row.url, root_hosts);
+    let (pagination_next, pagination_prev) = extract_pagination_links(&doc, &row.url, policy);
 
     if !title.is_empty() {
         row.title = title;
@@ -2719,6 +4652,8 @@ fn apply_rendered_html_to_row(row: &mut CrawlRow, html: &str, root_host: Option<
     row.h1_count = h1_count;
     row.h2_count = h2_count;
     row.canonical = canonical;
+    row.pagination_next = pagination_next;
+    row.pagination_prev = pagination_prev;
     row.word_count = word_count;
     row.size = html.as_bytes().len();
     row.image_count = image_count;
@@ -2727,29 +4662,29 @@ fn apply_rendered_html_to_row(row: &mut CrawlRow, html: &str, root_host: Option<
     row.internal_link_count = internal_link_count;
     row.external_link_count = external_link_count;
     row.link_count = internal_link_count + external_link_count;
+    row.mixed_content_count = mixed_content_count;
+    row.insecure_link_count = insecure_link_count;
     row.mime = "text/html".to_string();
     row.indexability = if (200..=299).contains(&row.status) && !noindex {
         "Indexable".to_string()
     } else {
         "Non-Indexable".to_string()
     };
-    row.issues = collect_row_issues(
-        row.status,
-        &row.retrieval_status,
-        true,
-        noindex,
-        row.title_length,
-        row.meta_length,
-        row.h1_count,
-        &row.canonical,
-        row.word_count,
-        row.image_missing_alt_count,
-        row.external_link_count,
+    let headers = row_headers(row);
+    let requested_as = policy
+        .linked_form(&row.url)
+        .unwrap_or_else(|| row.url.clone());
+    analyze_row(
+        analyzers,
+        row,
+        &requested_as,
+        Some(&doc),
+        Some(html),
+        &headers,
     );
-    row.seo_score = compute_seo_score(&row.issues);
 }
 
-fn unretrieved_row(url: String, reason: String) -> CrawlRow {
+pub(crate) fn unretrieved_row(url: String, reason: String) -> CrawlRow {
     let reason_len = reason.chars().count();
     let issues = vec![SeoIssue::NotRetrieved];
     CrawlRow {
@@ -2778,6 +4713,21 @@ fn unretrieved_row(url: String, reason: String) -> CrawlRow {
         image_count: 0,
         image_missing_alt_count: 0,
         structured_data_count: 0,
+        mixed_content_count: 0,
+        insecure_link_count: 0,
+        strict_transport_security: String::new(),
+        content_security_policy: String::new(),
+        x_content_type_options: String::new(),
+        x_frame_options: String::new(),
+        referrer_policy: String::new(),
+        cache_control: String::new(),
+        etag: String::new(),
+        vary: String::new(),
+        content_encoding: String::new(),
+        change_status: String::new(),
+        pagination_next: String::new(),
+        pagination_prev: String::new(),
+        extra_fields: BTreeMap::new(),
         seo_score: compute_seo_score(&issues),
         issues,
         crawl_timestamp: Utc::now().to_rfc3339(),
@@ -2796,6 +4746,64 @@ fn header_value(page: &spider::page::Page, name: &'static str) -> Option<String>
     })
 }
 
+fn response_header(headers: &reqwest::header::HeaderMap, name: &'static str) -> String {
+    headers
+        .get(name)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.to_string())
+        .unwrap_or_default()
+}
+
+/// A 2xx HTML page whose Content-Encoding the fetch could not see: spider's
+/// client decodes bodies and drops the header.
+fn needs_content_encoding(page: &Page) -> bool {
+    let is_html = header_value(page, "content-type")
+        .map(|v| v.to_ascii_lowercase().contains("html"))
+        .unwrap_or(false);
+    page.status_code.is_success() && is_html && header_value(page, "content-encoding").is_none()
+}
+
+fn set_content_encoding(page: &mut Page, encoding: &str) {
+    if !needs_content_encoding(page) {
+        return;
+    }
+    if let Ok(value) = spider::reqwest::header::HeaderValue::from_str(encoding) {
+        page.headers
+            .get_or_insert_with(Default::default)
+            .insert(spider::reqwest::header::CONTENT_ENCODING, value);
+    }
+}
+
+/// `--probe-compression`: asks the server again with a HEAD request on the
+/// undecoded client, for pages whose encoding no other response revealed.
+async fn probe_content_encoding(
+    mut page: Page,
+    client: reqwest::Client,
+    credentials: reqwest::header::HeaderMap,
+) -> Page {
+    let url = page.get_url_final().to_string();
+    let response = client
+        .head(&url)
+        .headers(credentials)
+        .header(reqwest::header::ACCEPT_ENCODING, "gzip, deflate, br")
+        .send()
+        .await;
+    if let Ok(response) = response
+        && response.status().is_success()
+    {
+        let encoding = response_header(response.headers(), "content-encoding");
+        set_content_encoding(
+            &mut page,
+            if encoding.is_empty() {
+                "identity"
+            } else {
+                &encoding
+            },
+        );
+    }
+    page
+}
+
 fn infer_mime_from_page(page: &spider::page::Page) -> String {
     let url = page.get_url().to_ascii_lowercase();
     if url.ends_with(".xml") {
@@ -2896,24 +4904,49 @@ fn extract_meta_description(doc: &Html) -> String {
     extract_meta_content(doc, "meta[name=\"twitter:description\"]")
 }
 
-fn extract_canonical(doc: &Html, page_url: &str) -> String {
-    let selector = match Selector::parse("link[rel=\"canonical\"]") {
-        Ok(s) => s,
-        Err(_) => return String::new(),
-    };
+fn extract_canonical(doc: &Html, page_url: &str, policy: &UrlPolicy) -> String {
+    canonical_href(doc, page_url)
+        .map(|url| normalize_crawl_url(&url, policy).unwrap_or(url))
+        .unwrap_or_default()
+}
 
-    doc.select(&selector)
+/// The canonical link resolved against the page but not normalized.
+pub(crate) fn canonical_href(doc: &Html, page_url: &str) -> Option<String> {
+    let selector = Selector::parse("link[rel=\"canonical\"]").ok()?;
+    let href = doc
+        .select(&selector)
         .next()
         .and_then(|el| el.value().attr("href"))
-        .map(normalize_text)
-        .and_then(|href| resolve_href(page_url, &href).or(Some(href)))
-        .unwrap_or_default()
+        .map(normalize_text)?;
+    resolve_href(page_url, &href).or(Some(href))
+}
+
+/// The rel="next" and rel="prev" targets, normalized like crawl links so
+/// they compare equal to the rows they point at.
+fn extract_pagination_links(doc: &Html, page_url: &str, policy: &UrlPolicy) -> (String, String) {
+    let first_link = |query: &str| {
+        let Ok(selector) = Selector::parse(query) else {
+            return String::new();
+        };
+        doc.select(&selector)
+            .find_map(|el| el.value().attr("href"))
+            .map(normalize_text)
+            .and_then(|href| resolve_href(page_url, &href))
+            .and_then(|url| normalize_crawl_url(&url, policy))
+            .unwrap_or_default()
+    };
+    (
+        first_link("link[rel~=\"next\"][href], a[rel~=\"next\"][href]"),
+        first_link(
+            "link[rel~=\"prev\"][href], link[rel~=\"previous\"][href], a[rel~=\"prev\"][href]",
+        ),
+    )
 }
 
 fn extract_crawl_links_with_breakdown(
     doc: &Html,
     page_url: &str,
-    root_host: Option<&str>,
+    root_hosts: Option<&[String]>,
 ) -> (Vec<String>, usize, usize) {
     let selector =
         match Selector::parse("link[rel=\"alternate\"][href], link[hreflang][href], a[href]") {
@@ -2931,7 +4964,7 @@ fn extract_crawl_links_with_breakdown(
             let Some(resolved) = resolve_href(page_url, href) else {
                 continue;
             };
-            if is_same_host(&resolved, root_host) {
+            if is_same_host(&resolved, root_hosts) {
                 internal_count += 1;
             } else {
                 external_count += 1;
@@ -2978,13 +5011,15 @@ fn resolve_href(page_url: &str, href: &str) -> Option<String> {
         return None;
     }
 
-    let resolved = if href.starts_with("http://") || href.starts_with("https://") {
-        href.to_string()
-    } else {
-        let base = Url::parse(page_url).ok()?;
-        base.join(href).ok()?.to_string()
+    let mut resolved = match Url::parse(href) {
+        Ok(url) => url,
+        Err(_) => Url::parse(page_url).ok()?.join(href).ok()?,
     };
-    normalize_crawl_url(&resolved)
+    if resolved.scheme() != "http" && resolved.scheme() != "https" {
+        return None;
+    }
+    resolved.set_fragment(None);
+    Some(resolved.to_string())
 }
 
 fn count_elements(doc: &Html, selector: &str) -> usize {
@@ -3021,83 +5056,969 @@ fn count_structured_data_blocks(doc: &Html) -> usize {
         .count()
 }
 
-fn collect_row_issues(
-    status: u16,
-    retrieval_status: &str,
-    is_html: bool,
-    noindex: bool,
-    title_length: usize,
-    meta_length: usize,
-    h1_count: usize,
-    canonical: &str,
-    word_count: usize,
-    image_missing_alt_count: usize,
-    external_link_count: usize,
-) -> Vec<SeoIssue> {
-    let mut issues = Vec::new();
-    if retrieval_status != "retrieved" {
-        issues.push(SeoIssue::NotRetrieved);
-        return issues;
+pub(crate) fn url_scheme(url: &str) -> Option<String> {
+    Url::parse(url)
+        .ok()
+        .map(|u| u.scheme().to_ascii_lowercase())
+}
+
+fn is_https_url(url: &str) -> bool {
+    url_scheme(url).as_deref() == Some("https")
+}
+
+fn count_mixed_content(doc: &Html, page_url: &str) -> usize {
+    if !is_https_url(page_url) {
+        return 0;
+    }
+    let selector = match Selector::parse(
+        "img[src], img[srcset], source[src], source[srcset], script[src], iframe[src], \
+         audio[src], video[src], video[poster], embed[src], object[data], \
+         link[rel=\"stylesheet\"][href], link[rel=\"preload\"][href], link[rel=\"icon\"][href]",
+    ) {
+        Ok(sel) => sel,
+        Err(_) => return 0,
+    };
+    doc.select(&selector)
+        .filter(|el| {
+            ["src", "href", "data", "poster"]
+                .iter()
+                .filter_map(|attr| el.value().attr(attr))
+                .any(|value| value.trim().to_ascii_lowercase().starts_with("http://"))
+                || el
+                    .value()
+                    .attr("srcset")
+                    .map(|srcset| {
+                        srcset.split(',').any(|candidate| {
+                            candidate.trim().to_ascii_lowercase().starts_with("http://")
+                        })
+                    })
+                    .unwrap_or(false)
+        })
+        .count()
+}
+
+fn count_insecure_internal_links(
+    doc: &Html,
+    page_url: &str,
+    root_hosts: Option<&[String]>,
+) -> usize {
+    if !is_https_url(page_url) {
+        return 0;
     }
-    if (400..=499).contains(&status) {
-        issues.push(SeoIssue::Http4xx);
+    let selector = match Selector::parse("a[href]") {
+        Ok(sel) => sel,
+        Err(_) => return 0,
+    };
+    doc.select(&selector)
+        .filter_map(|el| el.value().attr("href"))
+        .filter_map(|href| resolve_href(page_url, href.trim()))
+        .filter(|resolved| url_scheme(resolved).as_deref() == Some("http"))
+        .filter(|resolved| is_same_host(resolved, root_hosts))
+        .count()
+}
+
+pub(crate) fn count_hreflang_protocol_mismatches(doc: &Html, page_url: &str) -> usize {
+    let Some(page_scheme) = url_scheme(page_url) else {
+        return 0;
+    };
+    let selector = match Selector::parse("link[hreflang][href]") {
+        Ok(sel) => sel,
+        Err(_) => return 0,
+    };
+    doc.select(&selector)
+        .filter_map(|el| el.value().attr("href"))
+        .filter_map(|href| resolve_href(page_url, href.trim()))
+        .filter(|resolved| url_scheme(resolved).is_some_and(|scheme| scheme != page_scheme))
+        .count()
+}
+
+pub(crate) fn canonical_protocol_mismatch(page_url: &str, canonical: &str) -> bool {
+    let canonical = canonical.trim();
+    if canonical.is_empty() {
+        return false;
     }
-    if (500..=599).contains(&status) {
-        issues.push(SeoIssue::Http5xx);
+    match (url_scheme(page_url), url_scheme(canonical)) {
+        (Some(page_scheme), Some(canonical_scheme)) => page_scheme != canonical_scheme,
+        _ => false,
     }
+}
+
+/// Page 2+ of a series whose canonical points back at the first page, which
+/// keeps the deeper pages (and what they link to) out of the index.
+pub(crate) fn paginated_canonical_to_first(url: &str, canonical: &str) -> bool {
+    let Some((key, Some(page))) = pagination_position(url) else {
+        return false;
+    };
+    page >= 2 && !canonical.trim().is_empty() && pagination_link_points_to(canonical, &key, 1)
+}
 
-    if !is_html || !(200..=299).contains(&status) {
+const MAX_URL_LENGTH: usize = 115;
+const MAX_URL_PARAMS: usize = 3;
+const SESSION_ID_PARAMS: [&str; 8] = [
+    "jsessionid",
+    "phpsessid",
+    "aspsessionid",
+    "sessionid",
+    "session_id",
+    "sessid",
+    "sid",
+    "cfid",
+];
+
+pub(crate) fn collect_url_issues(url: &str, canonical: &str) -> Vec<SeoIssue> {
+    let mut issues = Vec::new();
+    let Ok(parsed) = Url::parse(url) else {
         return issues;
+    };
+
+    // The serialized path is percent-encoded, so decode it before looking at characters.
+    let path = parsed.path();
+    let decoded_path = percent_decode_path(path);
+    if decoded_path.chars().any(|ch| ch.is_ascii_uppercase()) {
+        issues.push(SeoIssue::UrlUppercase);
+    }
+    if decoded_path.contains('_') {
+        issues.push(SeoIssue::UrlUnderscores);
+    }
+    if !decoded_path.is_ascii() {
+        issues.push(SeoIssue::UrlNonAscii);
+    }
+    if path.contains("//") {
+        issues.push(SeoIssue::UrlDoubleSlash);
+    }
+    if url.chars().count() > MAX_URL_LENGTH {
+        issues.push(SeoIssue::UrlTooLong);
     }
 
-    if noindex {
-        issues.push(SeoIssue::Noindex);
+    let param_names = parsed
+        .query_pairs()
+        .map(|(name, _)| name.to_ascii_lowercase())
+        .collect::<Vec<_>>();
+    if param_names.len() > MAX_URL_PARAMS {
+        issues.push(SeoIssue::UrlTooManyParams);
     }
-    if title_length == 0 {
-        issues.push(SeoIssue::MissingTitle);
-    } else if title_length < 15 {
-        issues.push(SeoIssue::TitleTooShort);
-    } else if title_length > 60 {
-        issues.push(SeoIssue::TitleTooLong);
+    let path_lower = path.to_ascii_lowercase();
+    if param_names
+        .iter()
+        .any(|name| SESSION_ID_PARAMS.contains(&name.as_str()))
+        || SESSION_ID_PARAMS
+            .iter()
+            .any(|name| path_lower.contains(&format!(";{name}=")))
+    {
+        issues.push(SeoIssue::UrlSessionId);
     }
 
-    if meta_length == 0 {
-        issues.push(SeoIssue::MissingMetaDescription);
-    } else if meta_length < 70 {
-        issues.push(SeoIssue::MetaDescriptionTooShort);
-    } else if meta_length > 160 {
-        issues.push(SeoIssue::MetaDescriptionTooLong);
+    if trailing_slash_mismatch(&parsed, canonical) {
+        issues.push(SeoIssue::TrailingSlashMismatch);
     }
 
-    if h1_count == 0 {
-        issues.push(SeoIssue::MissingH1);
-    } else if h1_count > 1 {
-        issues.push(SeoIssue::MultipleH1);
+    issues
+}
+
+fn percent_decode_path(path: &str) -> String {
+    let bytes = path.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        if bytes[index] == b'%' {
+            if let Some(byte) = path
+                .get(index + 1..index + 3)
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+            {
+                decoded.push(byte);
+                index += 3;
+                continue;
+            }
+        }
+        decoded.push(bytes[index]);
+        index += 1;
     }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
 
-    if canonical.trim().is_empty() {
-        issues.push(SeoIssue::MissingCanonical);
+fn trailing_slash_mismatch(url: &Url, canonical: &str) -> bool {
+    let Ok(canonical) = Url::parse(canonical.trim()) else {
+        return false;
+    };
+    if url.path() == canonical.path()
+        || !url.host_str().eq(&canonical.host_str())
+        || url.query() != canonical.query()
+    {
+        return false;
     }
+    url.path().trim_end_matches('/') == canonical.path().trim_end_matches('/')
+}
 
-    if word_count < 120 {
-        issues.push(SeoIssue::LowWordCount);
+pub(crate) fn collect_header_issues(row: &CrawlRow) -> Vec<SeoIssue> {
+    let mut issues = Vec::new();
+    if !row.is_header_audit_candidate() {
+        return issues;
     }
 
-    if image_missing_alt_count > 0 {
-        issues.push(SeoIssue::ImagesMissingAlt);
+    let csp = row.content_security_policy.to_ascii_lowercase();
+    if csp.trim().is_empty() {
+        issues.push(SeoIssue::MissingContentSecurityPolicy);
+    }
+    if !row
+        .x_content_type_options
+        .to_ascii_lowercase()
+        .contains("nosniff")
+    {
+        issues.push(SeoIssue::MissingXContentTypeOptions);
+    }
+    if row.x_frame_options.trim().is_empty() && !csp.contains("frame-ancestors") {
+        issues.push(SeoIssue::MissingXFrameOptions);
+    }
+    if row.referrer_policy.trim().is_empty() {
+        issues.push(SeoIssue::MissingReferrerPolicy);
+    }
+    if row.cache_control.trim().is_empty() {
+        issues.push(SeoIssue::MissingCacheControl);
+    }
+    if row.etag.trim().is_empty() && row.last_modified.trim().is_empty() {
+        issues.push(SeoIssue::MissingCacheValidator);
     }
 
-    if external_link_count > 60 {
-        issues.push(SeoIssue::TooManyExternalLinks);
+    // An empty encoding means it was never probed; "identity" means the server declined to
+    // compress.
+    let encoding = row.content_encoding.trim().to_ascii_lowercase();
+    let vary = row.vary.to_ascii_lowercase();
+    if encoding == "identity" {
+        issues.push(SeoIssue::UncompressedResponse);
+    } else if !encoding.is_empty() && !vary.contains("accept-encoding") && !vary.contains('*') {
+        issues.push(SeoIssue::MissingVaryAcceptEncoding);
     }
 
     issues
 }
 
-fn compute_seo_score(issues: &[SeoIssue]) -> u8 {
+pub(crate) fn compute_seo_score(issues: &[SeoIssue]) -> u8 {
     let penalty = issues
         .iter()
         .map(|issue| issue.penalty() as u16)
         .sum::<u16>();
     (100u16.saturating_sub(penalty)) as u8
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn normalize(raw: &str, policy: &UrlPolicy) -> String {
+        normalize_crawl_url(raw, policy).unwrap()
+    }
+
+    #[test]
+    fn normalize_strips_tracking_params_and_fragments() {
+        let policy = UrlPolicy::default();
+        assert_eq!(
+            normalize(
+                "https://example.com/a?utm_source=x&id=3&gclid=9#top",
+                &policy
+            ),
+            "https://example.com/a?id=3"
+        );
+        assert_eq!(
+            normalize("https://example.com/a?utm_medium=x", &policy),
+            "https://example.com/a"
+        );
+        assert_eq!(normalize_crawl_url("mailto:a@example.com", &policy), None);
+        assert_eq!(normalize_crawl_url("  ", &policy), None);
+    }
+
+    #[test]
+    fn normalize_keeps_untouched_queries_verbatim() {
+        let policy = UrlPolicy::default();
+        assert_eq!(
+            normalize("https://example.com/s?q=a+b&b=2&a=1", &policy),
+            "https://example.com/s?q=a+b&b=2&a=1"
+        );
+    }
+
+    #[test]
+    fn normalize_applies_strip_and_keep_lists() {
+        let strip = UrlPolicy {
+            strip_params: vec!["sessionid".to_string(), "sort*".to_string()],
+            ..UrlPolicy::default()
+        };
+        assert_eq!(
+            normalize(
+                "https://example.com/c?SessionId=1&sort_by=price&page=2",
+                &strip
+            ),
+            "https://example.com/c?page=2"
+        );
+
+        let keep = UrlPolicy {
+            keep_params: vec!["page".to_string()],
+            ..UrlPolicy::default()
+        };
+        assert_eq!(
+            normalize("https://example.com/c?color=red&page=2", &keep),
+            "https://example.com/c?page=2"
+        );
+
+        let none = UrlPolicy {
+            strip_all_params: true,
+            ..UrlPolicy::default()
+        };
+        assert_eq!(
+            normalize("https://example.com/c?page=2", &none),
+            "https://example.com/c"
+        );
+    }
+
+    #[test]
+    fn normalize_sorts_params() {
+        let policy = UrlPolicy {
+            sort_params: true,
+            ..UrlPolicy::default()
+        };
+        assert_eq!(
+            normalize("https://example.com/s?b=2&a=1&utm_source=x", &policy),
+            "https://example.com/s?a=1&b=2"
+        );
+        assert_eq!(
+            normalize("https://example.com/s?a=2&a=1", &policy),
+            "https://example.com/s?a=1&a=2"
+        );
+    }
+
+    #[test]
+    fn normalize_rewrites_paths() {
+        let lower = UrlPolicy {
+            lowercase_paths: true,
+            ..UrlPolicy::default()
+        };
+        assert_eq!(
+            normalize("https://Example.com/Shop/Item?Q=A", &lower),
+            "https://example.com/shop/item?Q=A"
+        );
+
+        let add = UrlPolicy {
+            trailing_slash: TrailingSlashArg::Add,
+            ..UrlPolicy::default()
+        };
+        assert_eq!(
+            normalize("https://example.com/shop", &add),
+            "https://example.com/shop/"
+        );
+        assert_eq!(
+            normalize("https://example.com/page.html", &add),
+            "https://example.com/page.html"
+        );
+
+        let remove = UrlPolicy {
+            trailing_slash: TrailingSlashArg::Remove,
+            ..UrlPolicy::default()
+        };
+        assert_eq!(
+            normalize("https://example.com/shop//", &remove),
+            "https://example.com/shop"
+        );
+        assert_eq!(
+            normalize("https://example.com/", &remove),
+            "https://example.com/"
+        );
+    }
+
+    #[test]
+    fn normalization_is_pure_until_a_row_is_emitted() {
+        let policy = UrlPolicy::default();
+        let links = vec!["https://example.com/a?utm_source=x".to_string()];
+        let kept = filter_crawlable_links(links, None, &policy);
+        assert_eq!(kept, ["https://example.com/a"]);
+        assert!(policy.collapsed_variants().is_empty());
+        policy.commit_variants("https://example.com/a");
+        assert_eq!(policy.collapsed_variants().len(), 1);
+    }
+
+    #[test]
+    fn url_hygiene_rules_flag_each_problem() {
+        let long = format!("https://example.com/{}", "a".repeat(100));
+        let cases = [
+            ("https://example.com/shop/item?id=3", vec![]),
+            ("https://example.com/Shop", vec![SeoIssue::UrlUppercase]),
+            (
+                "https://example.com/my_page",
+                vec![SeoIssue::UrlUnderscores],
+            ),
+            (
+                "https://example.com/my%5Fpage",
+                vec![SeoIssue::UrlUnderscores],
+            ),
+            ("https://example.com/caf%C3%A9", vec![SeoIssue::UrlNonAscii]),
+            ("https://example.com/a//b", vec![SeoIssue::UrlDoubleSlash]),
+            (long.as_str(), vec![SeoIssue::UrlTooLong]),
+            ("https://example.com/s?a=1&b=2&c=3", vec![]),
+            (
+                "https://example.com/s?a=1&b=2&c=3&d=4",
+                vec![SeoIssue::UrlTooManyParams],
+            ),
+            (
+                "https://example.com/cart?SID=abc",
+                vec![SeoIssue::UrlSessionId],
+            ),
+            (
+                "https://example.com/cart;jsessionid=123",
+                vec![SeoIssue::UrlSessionId],
+            ),
+        ];
+        for (url, expected) in cases {
+            assert_eq!(collect_url_issues(url, ""), expected, "{url}");
+        }
+    }
+
+    #[test]
+    fn trailing_slash_mismatch_compares_link_and_canonical() {
+        assert_eq!(
+            collect_url_issues("https://example.com/shop/", "https://example.com/shop"),
+            [SeoIssue::TrailingSlashMismatch]
+        );
+        assert_eq!(
+            collect_url_issues("https://example.com/shop", " https://example.com/shop/ "),
+            [SeoIssue::TrailingSlashMismatch]
+        );
+        for (url, canonical) in [
+            ("https://example.com/shop/", "https://example.com/shop/"),
+            ("https://example.com/shop/", "https://other.example/shop"),
+            ("https://example.com/shop/?p=2", "https://example.com/shop"),
+            ("https://example.com/shop/", "/shop"),
+        ] {
+            assert!(
+                collect_url_issues(url, canonical).is_empty(),
+                "{url} -> {canonical}"
+            );
+        }
+    }
+
+    #[test]
+    fn https_checks_flag_insecure_resources_links_and_alternates() {
+        let doc = Html::parse_document(
+            r#"<html><head>
+            <link rel="canonical" href="http://example.com/page">
+            <link rel="stylesheet" href="http://example.com/site.css">
+            <link rel="stylesheet" href="https://example.com/secure.css">
+            <link rel="alternate" hreflang="de" href="http://example.com/de/page">
+            <link rel="alternate" hreflang="fr" href="https://example.com/fr/page">
+            <link rel="alternate" hreflang="en" href="/en/page">
+            </head><body>
+            <img src="http://cdn.example.net/a.png">
+            <img srcset="https://example.com/s.png 1x, http://example.com/l.png 2x">
+            <script src="//example.com/app.js"></script>
+            <iframe src="HTTP://video.example.net/embed"></iframe>
+            <a href="http://example.com/about">About</a>
+            <a href="/contact">Contact</a>
+            <a href="http://other.example/">Elsewhere</a>
+            </body></html>"#,
+        );
+        let roots = ["example.com".to_string()];
+        let (https, http) = ("https://example.com/page", "http://example.com/page");
+        assert_eq!(count_mixed_content(&doc, https), 4);
+        assert_eq!(count_insecure_internal_links(&doc, https, Some(&roots)), 1);
+        assert_eq!(count_hreflang_protocol_mismatches(&doc, https), 1);
+        assert!(canonical_protocol_mismatch(https, http));
+        assert!(!canonical_protocol_mismatch(https, https));
+        assert!(!canonical_protocol_mismatch(https, " "));
+
+        // Over plain HTTP nothing is mixed; the HTTPS alternate is the odd one out.
+        assert_eq!(count_mixed_content(&doc, http), 0);
+        assert_eq!(count_insecure_internal_links(&doc, http, Some(&roots)), 0);
+        assert_eq!(count_hreflang_protocol_mismatches(&doc, http), 1);
+        assert!(!canonical_protocol_mismatch(http, http));
+    }
+
+    #[test]
+    fn header_issues_flag_missing_and_weak_headers() {
+        let page = |headers: &[(&str, &str)]| {
+            let mut row = unretrieved_row("https://example.com/".to_string(), String::new());
+            row.status = 200;
+            row.retrieval_status = "retrieved".to_string();
+            row.mime = "text/html".to_string();
+            for (name, value) in headers {
+                let field = match *name {
+                    "content-security-policy" => &mut row.content_security_policy,
+                    "x-content-type-options" => &mut row.x_content_type_options,
+                    "x-frame-options" => &mut row.x_frame_options,
+                    "referrer-policy" => &mut row.referrer_policy,
+                    "cache-control" => &mut row.cache_control,
+                    "etag" => &mut row.etag,
+                    "last-modified" => &mut row.last_modified,
+                    "vary" => &mut row.vary,
+                    "content-encoding" => &mut row.content_encoding,
+                    _ => unreachable!("{name}"),
+                };
+                *field = value.to_string();
+            }
+            row
+        };
+
+        assert_eq!(
+            collect_header_issues(&page(&[])),
+            [
+                SeoIssue::MissingContentSecurityPolicy,
+                SeoIssue::MissingXContentTypeOptions,
+                SeoIssue::MissingXFrameOptions,
+                SeoIssue::MissingReferrerPolicy,
+                SeoIssue::MissingCacheControl,
+                SeoIssue::MissingCacheValidator,
+            ]
+        );
+
+        // frame-ancestors stands in for X-Frame-Options.
+        let hardened = [
+            (
+                "content-security-policy",
+                "default-src 'self'; frame-ancestors 'none'",
+            ),
+            ("x-content-type-options", "nosniff"),
+            ("referrer-policy", "strict-origin"),
+            ("cache-control", "max-age=60"),
+            ("last-modified", "Tue, 01 Sep 2026 10:00:00 GMT"),
+        ];
+        assert!(collect_header_issues(&page(&hardened)).is_empty());
+
+        let encoded = |encoding: &str, vary: &str| {
+            let mut headers = hardened.to_vec();
+            headers.push(("content-encoding", encoding));
+            headers.push(("vary", vary));
+            collect_header_issues(&page(&headers))
+        };
+        assert!(encoded("gzip", "Accept-Encoding").is_empty());
+        assert!(encoded("br", "*").is_empty());
+        assert_eq!(
+            encoded("gzip", "Cookie"),
+            [SeoIssue::MissingVaryAcceptEncoding]
+        );
+        // "identity" is what a probe records when the server did not compress.
+        assert_eq!(encoded("identity", ""), [SeoIssue::UncompressedResponse]);
+        // Empty: never probed.
+        assert!(encoded("", "").is_empty());
+
+        let mut image = page(&[]);
+        image.mime = "image/png".to_string();
+        let mut missing = page(&[]);
+        missing.status = 404;
+        assert!(collect_header_issues(&image).is_empty());
+        assert!(collect_header_issues(&missing).is_empty());
+    }
+
+    #[test]
+    fn facet_cap_keeps_pages_that_were_fetched() {
+        let policy = UrlPolicy {
+            max_facet_variants: 1,
+            ..UrlPolicy::default()
+        };
+        assert!(policy.admit("https://example.com/c?color=red"));
+        assert!(!policy.admit("https://example.com/c?color=blue"));
+
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let row = unretrieved_row("https://example.com/c?color=blue".into(), "timeout".into());
+        let event = CrawlEvent::Page {
+            row: Box::new(row),
+            discovered_links: Vec::new(),
+        };
+        send_within_budget(&tx, &CrawlBudget::unlimited(), &policy, event);
+        assert!(matches!(rx.try_recv(), Ok(CrawlEvent::Page { .. })));
+        assert_eq!(policy.facet_caps()[0].dropped, 1);
+    }
+
+    /// Serves `pages` (path and body) on a loopback port; returns its origin
+    /// and the paths requested so far.
+    fn serve_site(
+        pages: &'static [(&'static str, &'static str)],
+    ) -> (String, Arc<std::sync::Mutex<Vec<String>>>) {
+        serve_responses(move |path| {
+            let (status, body) = pages
+                .iter()
+                .find(|(page, _)| *page == path)
+                .map_or(("404 Not Found", ""), |(_, body)| ("200 OK", *body));
+            format!(
+                "HTTP/1.1 {status}\r\nContent-Type: text/html\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            )
+        })
+    }
+
+    /// Answers every request with `respond(path)`, a raw HTTP response.
+    fn serve_responses(
+        respond: impl Fn(&str) -> String + Send + 'static,
+    ) -> (String, Arc<std::sync::Mutex<Vec<String>>>) {
+        use std::io::{BufRead, BufReader};
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let origin = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let log = requests.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else {
+                    continue;
+                };
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut line = String::new();
+                let _ = reader.read_line(&mut line);
+                let path = line.split_whitespace().nth(1).unwrap_or("/").to_string();
+                let mut header = String::new();
+                while reader.read_line(&mut header).is_ok_and(|read| read > 2) {
+                    header.clear();
+                }
+                log.lock().unwrap().push(path.clone());
+                let _ = stream.write_all(respond(&path).as_bytes());
+            }
+        });
+        (origin, requests)
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn redirect_checks_report_why_the_trace_stopped() {
+        let (origin, requests) = serve_responses(|path| {
+            let moved = "301 Moved Permanently";
+            let (status, location) = match path {
+                "/loop-a" => (moved, "Location: /loop-b\r\n".to_string()),
+                "/loop-b" => (moved, "Location: /loop-a\r\n".to_string()),
+                "/no-location" => (moved, String::new()),
+                "/moved" => (moved, "Location: /new\r\n".to_string()),
+                "/gone" => ("410 Gone", String::new()),
+                path if path.starts_with("/hop") => (moved, format!("Location: {path}/x\r\n")),
+                _ => ("200 OK", String::new()),
+            };
+            format!("HTTP/1.1 {status}\r\n{location}Content-Length: 0\r\nConnection: close\r\n\r\n")
+        });
+        let closed = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        let urls = [
+            "/no-location",
+            "/loop-a",
+            "/hop",
+            "/page",
+            "/moved",
+            "/gone",
+        ]
+        .iter()
+        .map(|path| format!("{origin}{path}"))
+        .chain(std::iter::once(format!("http://{closed}/old")));
+        let entries = urls
+            .map(|url| {
+                (
+                    url,
+                    ListExpectation {
+                        status: None,
+                        redirect_target: Some(format!("{origin}/new")),
+                    },
+                )
+            })
+            .collect();
+        let checks = verify_redirect_mapping(entries, 2, &HttpOptions::default())
+            .await
+            .unwrap();
+        let outcomes = checks.iter().map(|check| check.outcome).collect::<Vec<_>>();
+        assert_eq!(
+            outcomes,
+            [
+                RedirectOutcome::MissingLocation,
+                RedirectOutcome::RedirectLoop,
+                RedirectOutcome::TooManyRedirects,
+                RedirectOutcome::NotRedirected,
+                RedirectOutcome::Match,
+                RedirectOutcome::BrokenTarget,
+                RedirectOutcome::RequestFailed,
+            ]
+        );
+        let statuses = checks
+            .iter()
+            .map(|check| check.final_status)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            statuses,
+            [
+                Some(301),
+                Some(301),
+                None,
+                Some(200),
+                Some(200),
+                Some(410),
+                None
+            ]
+        );
+        // The final hop's status comes from the trace, not another request.
+        let requests = requests.lock().unwrap();
+        for path in ["/page", "/new", "/gone", "/loop-a"] {
+            assert_eq!(
+                requests
+                    .iter()
+                    .filter(|requested| *requested == path)
+                    .count(),
+                1,
+                "{path}"
+            );
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn webdriver_control_bypasses_the_proxy() {
+        let (endpoint, requests) = serve_responses(|path| {
+            let body = if path == "/session" {
+                r#"{"value":{"sessionId":"s1","capabilities":{}}}"#
+            } else {
+                r#"{"value":null}"#
+            };
+            format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            )
+        });
+        // A proxy nothing listens on, with loopback not exempted: any request
+        // through it fails.
+        let closed = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        let mut http = HttpOptions::default();
+        http.proxy = Some(Url::parse(&format!("http://{closed}")).unwrap());
+
+        webdriver_preflight(
+            &endpoint,
+            BrowserArg::Chrome,
+            true,
+            "https://example.com/",
+            &http,
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            *requests.lock().unwrap(),
+            ["/session", "/session/s1/url", "/session/s1"]
+        );
+        let caps = webdriver_capabilities(BrowserArg::Chrome, true, &http);
+        assert_eq!(
+            caps["capabilities"]["alwaysMatch"]["proxy"]["httpProxy"],
+            closed.to_string()
+        );
+    }
+
+    #[test]
+    fn scope_merges_seeds_and_allowed_hosts() {
+        let seeds = [
+            "https://Example.com/a",
+            "http://blog.example.com:8080/",
+            "https://example.com/b",
+            "not a url",
+        ]
+        .map(String::from);
+        let allowed = [" Shop.Example.com ", "", "blog.example.com"].map(String::from);
+        let hosts = crawl_scope_hosts(&seeds, &allowed);
+        assert_eq!(
+            hosts,
+            ["blog.example.com", "example.com", "shop.example.com"]
+        );
+        // Seeds keep their scheme and port; allowed hosts take the first seed's scheme.
+        assert_eq!(
+            scope_origins(&seeds, &hosts),
+            [
+                "https://example.com",
+                "http://blog.example.com:8080",
+                "https://shop.example.com",
+            ]
+        );
+
+        let links = [
+            "https://shop.example.com/cart",
+            "http://blog.example.com:8080/post",
+            "https://example.org/",
+            "https://www.example.com/",
+        ]
+        .map(String::from)
+        .to_vec();
+        assert_eq!(
+            filter_crawlable_links(links, Some(&hosts), &UrlPolicy::default()),
+            [
+                "https://shop.example.com/cart",
+                "http://blog.example.com:8080/post",
+            ]
+        );
+    }
+
+    #[test]
+    fn credentials_cover_every_seed_and_allowed_host() {
+        let cli = Cli::try_parse_from([
+            "gh0st",
+            "--header",
+            "X-Token: abc",
+            "--allow-host",
+            "cdn.example.com, ",
+            "https://example.com",
+            "https://Blog.example.com/",
+        ])
+        .unwrap();
+        let http = HttpOptions::from_cli(&cli).unwrap();
+        assert_eq!(
+            http.scope_hosts,
+            ["blog.example.com", "cdn.example.com", "example.com"]
+        );
+        for url in [
+            "https://example.com/a",
+            "https://blog.example.com/b",
+            "https://cdn.example.com/c.js",
+        ] {
+            assert!(http.credentials_for(url).contains_key("x-token"), "{url}");
+        }
+        assert!(http.credentials_for("https://example.org/").is_empty());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn not_modified_pages_carry_the_previous_row_forward() {
+        let (origin, requests) = serve_responses(|_| {
+            "HTTP/1.1 304 Not Modified\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                .to_string()
+        });
+        let url = format!("{origin}/page");
+        let mut previous = unretrieved_row(url.clone(), String::new());
+        previous.status = 200;
+        previous.retrieval_status = "retrieved".to_string();
+        previous.indexability = "Indexable".to_string();
+        previous.mime = "text/html".to_string();
+        previous.title = "Old title".to_string();
+        previous.word_count = 300;
+        previous.etag = "\"v1\"".to_string();
+        previous.issues = vec![SeoIssue::MissingMetaDescription];
+        previous.seo_score = compute_seo_score(&previous.issues);
+        previous.crawl_timestamp = "2026-01-01T00:00:00+00:00".to_string();
+        let links = vec![
+            format!("{origin}/next"),
+            "https://elsewhere.example/".to_string(),
+        ];
+
+        let http = HttpOptions::default();
+        let fetch = FetchContext {
+            retries: 1,
+            retry_5xx: 0,
+            concurrency: Arc::new(AtomicUsize::new(1)),
+            shutdown_requested: Arc::default(),
+            root_hosts: Some(vec!["127.0.0.1".to_string()]),
+            http,
+        };
+        let clients = FetchClients::build(&fetch.http).unwrap();
+        let events =
+            process_conditional_url(url.clone(), Some((previous.clone(), links)), fetch, clients)
+                .await;
+
+        let [
+            CrawlEvent::Page {
+                row,
+                discovered_links,
+            },
+        ] = events.as_slice()
+        else {
+            panic!("expected one page event, got {events:?}");
+        };
+        assert_eq!(row.change_status, "unchanged");
+        assert_eq!(row.url, url);
+        assert_eq!(row.status, 200);
+        assert_eq!(row.title, previous.title);
+        assert_eq!(row.word_count, previous.word_count);
+        assert_eq!(row.etag, previous.etag);
+        assert_eq!(row.issues, previous.issues);
+        assert_eq!(row.seo_score, previous.seo_score);
+        assert_ne!(row.crawl_timestamp, previous.crawl_timestamp);
+        assert_eq!(discovered_links, &[format!("{origin}/next")]);
+        assert_eq!(*requests.lock().unwrap(), ["/page"]);
+    }
+
+    #[test]
+    fn credentials_keep_spider_on_scope_hosts() {
+        let cli = Cli::try_parse_from([
+            "gh0st",
+            "--basic-auth",
+            "user:secret",
+            "--subdomains",
+            "--tld",
+            "https://example.com",
+        ])
+        .unwrap();
+        let http = HttpOptions::from_cli(&cli).unwrap();
+        let website = configure_website(&cli, &cli.url, &http, &CrawlBudget::unlimited());
+        assert!(!website.configuration.subdomains);
+        assert!(!website.configuration.tld);
+        let authorization = reqwest::header::AUTHORIZATION;
+        assert!(
+            http.credentials_for("https://example.com/a")
+                .contains_key(&authorization)
+        );
+        assert!(http.credentials_for("https://blog.example.com/").is_empty());
+        assert!(http.credentials_for("https://example.org/").is_empty());
+
+        let cli = Cli::try_parse_from(["gh0st", "--subdomains", "https://example.com"]).unwrap();
+        let http = HttpOptions::from_cli(&cli).unwrap();
+        let website = configure_website(&cli, &cli.url, &http, &CrawlBudget::unlimited());
+        assert!(website.configuration.subdomains);
+    }
+
+    #[test]
+    fn excluded_pages_are_not_followed() {
+        let policy = UrlPolicy::default();
+        let frontier = CrawlFrontier::default();
+        let url = "https://example.com/tag/x?utm_source=a";
+        assert!(follows_page_links(url, &policy, &frontier));
+        frontier.exclude("*/tag/*");
+        assert!(!follows_page_links(url, &policy, &frontier));
+        assert!(follows_page_links(
+            "https://example.com/a",
+            &policy,
+            &frontier
+        ));
+    }
+
+    // spider cannot skip a link once it is queued, so an excluded page is
+    // still fetched; it must not be exported and its links not followed.
+    #[tokio::test(flavor = "multi_thread")]
+    async fn excluded_pages_are_dropped_from_the_crawl() {
+        let (origin, requests) = serve_site(&[
+            (
+                "/",
+                "<html><body><a href=\"/a\">a</a> <a href=\"/b\">b</a></body></html>",
+            ),
+            ("/a", "<html><body>a</body></html>"),
+            ("/b", "<html><body><a href=\"/d\">d</a></body></html>"),
+            ("/d", "<html><body>d</body></html>"),
+        ]);
+        let cli = Cli::try_parse_from(["gh0st", "--", &origin]).unwrap();
+        let frontier = Arc::new(CrawlFrontier::default());
+        frontier.exclude("*/b");
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let (_control, control_rx) = mpsc::unbounded_channel();
+        let crawl = tokio::spawn(run_crawler(
+            cli,
+            AnalyzerRegistry::default(),
+            None,
+            frontier.clone(),
+            tx,
+            control_rx,
+        ));
+
+        let mut urls = Vec::new();
+        let finished = tokio::time::timeout(Duration::from_secs(30), async {
+            while let Some(event) = rx.recv().await {
+                match event {
+                    CrawlEvent::Page { row, .. } => urls.push(row.url),
+                    CrawlEvent::Unretrieved { url, .. } => urls.push(url),
+                    CrawlEvent::Finished => return true,
+                    _ => {}
+                }
+            }
+            false
+        })
+        .await;
+        crawl.abort();
+        assert_eq!(finished, Ok(true));
+
+        urls.sort();
+        urls.dedup();
+        assert_eq!(urls, [format!("{origin}/"), format!("{origin}/a")]);
+        let requests = requests.lock().unwrap();
+        assert!(!requests.iter().any(|path| path == "/d"), "{requests:?}");
+        assert_eq!(frontier.page(0, 10).0, 0);
+    }
+}
@@ -1,4 +1,6 @@
-const CSV_HEADERS: [&str; 31] = [
+use super::*;
+
+pub(crate) const CSV_HEADERS: [&str; 48] = [
     "url",
     "status",
     "mime",
@@ -24,16 +26,33 @@ const CSV_HEADERS: [&str; 31] = [
     "image_count",
     "image_missing_alt_count",
     "structured_data_count",
+    "mixed_content_count",
+    "insecure_link_count",
+    "strict_transport_security",
+    "content_security_policy",
+    "x_content_type_options",
+    "x_frame_options",
+    "referrer_policy",
+    "cache_control",
+    "etag",
+    "vary",
+    "content_encoding",
+    "change_status",
+    "pagination_next",
+    "pagination_prev",
+    "extra_fields",
     "seo_score",
     "issue_count",
     "issues",
+    "highest_severity",
+    "custom_issues",
     "outgoing_links",
     "crawl_timestamp",
     "crawl_quality_bucket",
 ];
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-struct ExportRecord {
+pub(crate) struct ExportRecord {
     url: String,
     status: u16,
     mime: String,
@@ -59,14 +78,78 @@ struct ExportRecord {
     image_count: usize,
     image_missing_alt_count: usize,
     structured_data_count: usize,
+    #[serde(default)]
+    mixed_content_count: usize,
+    #[serde(default)]
+    insecure_link_count: usize,
+    #[serde(default)]
+    strict_transport_security: String,
+    #[serde(default)]
+    content_security_policy: String,
+    #[serde(default)]
+    x_content_type_options: String,
+    #[serde(default)]
+    x_frame_options: String,
+    #[serde(default)]
+    referrer_policy: String,
+    #[serde(default)]
+    cache_control: String,
+    #[serde(default)]
+    etag: String,
+    #[serde(default)]
+    vary: String,
+    #[serde(default)]
+    content_encoding: String,
+    #[serde(default)]
+    change_status: String,
+    #[serde(default)]
+    pagination_next: String,
+    #[serde(default)]
+    pagination_prev: String,
+    #[serde(default)]
+    extra_fields: BTreeMap<String, String>,
     seo_score: u8,
     issue_count: usize,
     issues: String,
+    #[serde(default)]
+    highest_severity: String,
+    #[serde(default)]
+    custom_issues: Vec<CustomIssueRecord>,
     outgoing_links: Vec<String>,
     crawl_timestamp: String,
     crawl_quality_bucket: String,
 }
 
+/// The definition of a custom issue on an exported row, so reloading the
+/// export keeps its penalty, severity and category.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct CustomIssueRecord {
+    label: String,
+    penalty: u8,
+    severity: String,
+    category: String,
+}
+
+impl CustomIssueRecord {
+    fn from_issue(issue: &CustomIssue) -> Self {
+        Self {
+            label: issue.label.clone(),
+            penalty: issue.penalty,
+            severity: issue.severity.label().to_string(),
+            category: issue.category.label().to_string(),
+        }
+    }
+
+    fn to_issue(&self) -> SeoIssue {
+        SeoIssue::custom_with(
+            &self.label,
+            self.penalty,
+            IssueSeverity::from_label(&self.severity).unwrap_or(IssueSeverity::Warning),
+            IssueCategory::from_label(&self.category).unwrap_or(IssueCategory::Content),
+        )
+    }
+}
+
 fn crawl_quality_bucket(seo_score: u8) -> &'static str {
     match seo_score {
         85..=100 => "excellent",
@@ -76,7 +159,7 @@ fn crawl_quality_bucket(seo_score: u8) -> &'static str {
     }
 }
 
-fn row_to_export_record(row: &CrawlRow, outgoing_links: &[String]) -> ExportRecord {
+pub(crate) fn row_to_export_record(row: &CrawlRow, outgoing_links: &[String]) -> ExportRecord {
     ExportRecord {
         url: row.url.clone(),
         status: row.status,
@@ -103,20 +186,59 @@ fn row_to_export_record(row: &CrawlRow, outgoing_links: &[String]) -> ExportReco
         image_count: row.image_count,
         image_missing_alt_count: row.image_missing_alt_count,
         structured_data_count: row.structured_data_count,
+        mixed_content_count: row.mixed_content_count,
+        insecure_link_count: row.insecure_link_count,
+        strict_transport_security: row.strict_transport_security.clone(),
+        content_security_policy: row.content_security_policy.clone(),
+        x_content_type_options: row.x_content_type_options.clone(),
+        x_frame_options: row.x_frame_options.clone(),
+        referrer_policy: row.referrer_policy.clone(),
+        cache_control: row.cache_control.clone(),
+        etag: row.etag.clone(),
+        vary: row.vary.clone(),
+        content_encoding: row.content_encoding.clone(),
+        change_status: row.change_status.clone(),
+        pagination_next: row.pagination_next.clone(),
+        pagination_prev: row.pagination_prev.clone(),
+        extra_fields: row.extra_fields.clone(),
         seo_score: row.seo_score,
         issue_count: row.issues.len(),
         issues: issues_to_csv(&row.issues),
+        highest_severity: row
+            .highest_severity()
+            .map(|severity| severity.label().to_string())
+            .unwrap_or_default(),
+        custom_issues: row
+            .issues
+            .iter()
+            .filter_map(|issue| match issue {
+                SeoIssue::Custom(custom) => Some(CustomIssueRecord::from_issue(custom)),
+                _ => None,
+            })
+            .collect(),
         outgoing_links: outgoing_links.to_vec(),
         crawl_timestamp: row.crawl_timestamp.clone(),
         crawl_quality_bucket: crawl_quality_bucket(row.seo_score).to_string(),
     }
 }
 
-fn export_record_to_row(record: ExportRecord) -> (CrawlRow, Vec<String>) {
+pub(crate) fn export_record_to_row(record: ExportRecord) -> (CrawlRow, Vec<String>) {
     let mut issues = record
         .issues
         .split('|')
-        .filter_map(SeoIssue::from_label)
+        .map(str::trim)
+        .filter(|label| !label.is_empty())
+        .map(|label| {
+            SeoIssue::from_label(label).unwrap_or_else(|| {
+                record
+                    .custom_issues
+                    .iter()
+                    .find(|custom| custom.label == label)
+                    .map(CustomIssueRecord::to_issue)
+                    // Exports written before custom definitions were saved.
+                    .unwrap_or_else(|| SeoIssue::custom(label, 0))
+            })
+        })
         .collect::<Vec<_>>();
     if issues.is_empty() && record.retrieval_status == "not_retrieved" {
         issues.push(SeoIssue::NotRetrieved);
@@ -149,6 +271,21 @@ fn export_record_to_row(record: ExportRecord) -> (CrawlRow, Vec<String>) {
             image_count: record.image_count,
             image_missing_alt_count: record.image_missing_alt_count,
             structured_data_count: record.structured_data_count,
+            mixed_content_count: record.mixed_content_count,
+            insecure_link_count: record.insecure_link_count,
+            strict_transport_security: record.strict_transport_security,
+            content_security_policy: record.content_security_policy,
+            x_content_type_options: record.x_content_type_options,
+            x_frame_options: record.x_frame_options,
+            referrer_policy: record.referrer_policy,
+            cache_control: record.cache_control,
+            etag: record.etag,
+            vary: record.vary,
+            content_encoding: record.content_encoding,
+            change_status: record.change_status,
+            pagination_next: record.pagination_next,
+            pagination_prev: record.pagination_prev,
+            extra_fields: record.extra_fields,
             seo_score: if record.seo_score == 0 && !issues.is_empty() {
                 compute_seo_score(&issues)
             } else {
@@ -161,8 +298,9 @@ fn export_record_to_row(record: ExportRecord) -> (CrawlRow, Vec<String>) {
     )
 }
 
-struct CsvSink {
+pub struct CsvSink {
     writer: csv::Writer<File>,
+    path: String,
 }
 
 impl CsvSink {
@@ -170,7 +308,10 @@ impl CsvSink {
         let file = File::create(output_path)?;
         let mut writer = csv::Writer::from_writer(file);
         writer.write_record(CSV_HEADERS)?;
-        Ok(Self { writer })
+        Ok(Self {
+            writer,
+            path: output_path.to_string(),
+        })
     }
 
     fn write_row(&mut self, row: &CrawlRow, outgoing_links: &[String]) -> io::Result<()> {
@@ -201,9 +342,34 @@ impl CsvSink {
             rec.image_count.to_string(),
             rec.image_missing_alt_count.to_string(),
             rec.structured_data_count.to_string(),
+            rec.mixed_content_count.to_string(),
+            rec.insecure_link_count.to_string(),
+            rec.strict_transport_security,
+            rec.content_security_policy,
+            rec.x_content_type_options,
+            rec.x_frame_options,
+            rec.referrer_policy,
+            rec.cache_control,
+            rec.etag,
+            rec.vary,
+            rec.content_encoding,
+            rec.change_status,
+            rec.pagination_next,
+            rec.pagination_prev,
+            if rec.extra_fields.is_empty() {
+                String::new()
+            } else {
+                serde_json::to_string(&rec.extra_fields).unwrap_or_default()
+            },
             rec.seo_score.to_string(),
             rec.issue_count.to_string(),
             rec.issues,
+            rec.highest_severity,
+            if rec.custom_issues.is_empty() {
+                String::new()
+            } else {
+                serde_json::to_string(&rec.custom_issues).unwrap_or_default()
+            },
             rec.outgoing_links.join("|"),
             rec.crawl_timestamp,
             rec.crawl_quality_bucket,
@@ -216,10 +382,11 @@ impl CsvSink {
     }
 }
 
-struct JsonSink {
+pub struct JsonSink {
     file: File,
     first: bool,
     closed: bool,
+    path: String,
 }
 
 impl JsonSink {
@@ -230,6 +397,7 @@ impl JsonSink {
             file,
             first: true,
             closed: false,
+            path: output_path.to_string(),
         })
     }
 
@@ -266,42 +434,49 @@ impl Drop for JsonSink {
     }
 }
 
-enum OutputSink {
-    Csv(CsvSink),
-    Json(JsonSink),
+pub enum OutputSink {
+    Csv(Box<CsvSink>),
+    Json(Box<JsonSink>),
 }
 
 impl OutputSink {
-    fn new(output_path: &str, format: DataFormat) -> io::Result<Self> {
+    pub fn new(output_path: &str, format: DataFormat) -> io::Result<Self> {
         match format {
-            DataFormat::Csv => Ok(OutputSink::Csv(CsvSink::new(output_path)?)),
-            DataFormat::Json => Ok(OutputSink::Json(JsonSink::new(output_path)?)),
+            DataFormat::Csv => Ok(OutputSink::Csv(Box::new(CsvSink::new(output_path)?))),
+            DataFormat::Json => Ok(OutputSink::Json(Box::new(JsonSink::new(output_path)?))),
         }
     }
 
-    fn write_row(&mut self, row: &CrawlRow, outgoing_links: &[String]) -> io::Result<()> {
+    pub fn write_row(&mut self, row: &CrawlRow, outgoing_links: &[String]) -> io::Result<()> {
         match self {
             OutputSink::Csv(sink) => sink.write_row(row, outgoing_links),
             OutputSink::Json(sink) => sink.write_row(row, outgoing_links),
         }
     }
 
-    fn flush(&mut self) -> io::Result<()> {
+    pub fn flush(&mut self) -> io::Result<()> {
         match self {
             OutputSink::Csv(sink) => sink.flush(),
             OutputSink::Json(sink) => sink.flush(),
         }
     }
 
-    fn finalize(&mut self) -> io::Result<()> {
+    pub fn finalize(&mut self) -> io::Result<()> {
         match self {
             OutputSink::Csv(sink) => sink.flush(),
             OutputSink::Json(sink) => sink.finalize(),
         }
     }
+
+    pub fn path(&self) -> &str {
+        match self {
+            OutputSink::Csv(sink) => &sink.path,
+            OutputSink::Json(sink) => &sink.path,
+        }
+    }
 }
 
-fn issues_to_csv(issues: &[SeoIssue]) -> String {
+pub(crate) fn issues_to_csv(issues: &[SeoIssue]) -> String {
     issues
         .iter()
         .map(|issue| issue.label())
@@ -390,9 +565,26 @@ fn load_rows_from_csv(path: &str) -> io::Result<Vec<(CrawlRow, Vec<String>)>> {
             structured_data_count: get(&["structured_data_count"])
                 .parse::<usize>()
                 .unwrap_or(0),
+            mixed_content_count: get(&["mixed_content_count"]).parse::<usize>().unwrap_or(0),
+            insecure_link_count: get(&["insecure_link_count"]).parse::<usize>().unwrap_or(0),
+            strict_transport_security: get(&["strict_transport_security"]),
+            content_security_policy: get(&["content_security_policy"]),
+            x_content_type_options: get(&["x_content_type_options"]),
+            x_frame_options: get(&["x_frame_options"]),
+            referrer_policy: get(&["referrer_policy"]),
+            cache_control: get(&["cache_control"]),
+            etag: get(&["etag"]),
+            vary: get(&["vary"]),
+            content_encoding: get(&["content_encoding"]),
+            change_status: get(&["change_status"]),
+            pagination_next: get(&["pagination_next"]),
+            pagination_prev: get(&["pagination_prev"]),
+            extra_fields: serde_json::from_str(&get(&["extra_fields"])).unwrap_or_default(),
             seo_score: get(&["seo_score"]).parse::<u8>().unwrap_or(0),
             issue_count: get(&["issue_count"]).parse::<usize>().unwrap_or(0),
             issues: issues_raw,
+            highest_severity: get(&["highest_severity"]),
+            custom_issues: serde_json::from_str(&get(&["custom_issues"])).unwrap_or_default(),
             outgoing_links: get(&["outgoing_links"])
                 .split('|')
                 .map(str::trim)
@@ -431,7 +623,7 @@ fn load_rows_from_json(path: &str) -> io::Result<Vec<(CrawlRow, Vec<String>)>> {
     Ok(out)
 }
 
-fn detect_data_format(path: &str, fallback: DataFormat) -> DataFormat {
+pub fn detect_data_format(path: &str, fallback: DataFormat) -> DataFormat {
     let lower = path.to_ascii_lowercase();
     if lower.ends_with(".json") {
         DataFormat::Json
@@ -442,14 +634,382 @@ fn detect_data_format(path: &str, fallback: DataFormat) -> DataFormat {
     }
 }
 
-fn load_rows_from_file(path: &str) -> io::Result<Vec<(CrawlRow, Vec<String>)>> {
+pub fn load_rows_from_file(path: &str) -> io::Result<Vec<(CrawlRow, Vec<String>)>> {
     match detect_data_format(path, DataFormat::Csv) {
         DataFormat::Csv => load_rows_from_csv(path),
         DataFormat::Json => load_rows_from_json(path),
     }
 }
 
-fn default_output_path(url: &str, format: DataFormat) -> String {
+/// Reads a Netscape-format cookie jar (as written by curl or browser export
+/// tools). Plain `name=value` lines are accepted too and apply to any host.
+pub(crate) fn load_cookie_jar(path: &str) -> Result<Vec<(String, String, String)>, String> {
+    let text =
+        fs::read_to_string(path).map_err(|e| format!("failed to read cookie jar {path}: {e}"))?;
+    let now = Utc::now().timestamp();
+    let mut cookies = Vec::new();
+    for line in text.lines() {
+        let line = line.trim_end_matches('\r');
+        let line = line.strip_prefix("#HttpOnly_").unwrap_or(line);
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let fields = line.split('\t').collect::<Vec<_>>();
+        if fields.len() >= 7 {
+            let expires = fields[4].trim().parse::<i64>().unwrap_or(0);
+            if expires > 0 && expires < now {
+                continue;
+            }
+            cookies.push((
+                fields[0].to_string(),
+                fields[5].to_string(),
+                fields[6].to_string(),
+            ));
+        } else if let Some((name, value)) = line.split_once('=') {
+            cookies.push((
+                String::new(),
+                name.trim().to_string(),
+                value.trim().to_string(),
+            ));
+        }
+    }
+    Ok(cookies)
+}
+
+/// Reads `--list` input: a sitemap, a CSV with a URL column, or plain text
+/// with one URL per line optionally followed by an expected status and/or
+/// redirect target. `-` reads from stdin.
+pub(crate) fn load_url_list(source: &str) -> Result<Vec<(String, ListExpectation)>, String> {
+    let text = if source == "-" {
+        let mut buf = String::new();
+        io::Read::read_to_string(&mut io::stdin(), &mut buf)
+            .map_err(|e| format!("failed to read URL list from stdin: {e}"))?;
+        buf
+    } else {
+        fs::read_to_string(source).map_err(|e| format!("failed to read URL list {source}: {e}"))?
+    };
+
+    let trimmed = text.trim_start_matches('\u{feff}').trim_start();
+    if trimmed.starts_with('<') {
+        return Ok(extract_xml_loc_values(trimmed)
+            .into_iter()
+            .map(|url| (url, ListExpectation::default()))
+            .collect());
+    }
+
+    let first_line = trimmed
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    if first_line.contains(',') && !first_line.contains("://") {
+        return load_url_list_csv(trimmed);
+    }
+
+    // Without a header each line is the URL, then an optional expected status
+    // and redirect target, separated by commas or whitespace.
+    let rows = if is_csv_list_line(&first_line) {
+        csv::ReaderBuilder::new()
+            .has_headers(false)
+            .flexible(true)
+            .comment(Some(b'#'))
+            .from_reader(trimmed.as_bytes())
+            .records()
+            .map(|record| {
+                record
+                    .map(|record| {
+                        record
+                            .iter()
+                            .map(|field| field.trim().to_string())
+                            .collect()
+                    })
+                    .map_err(|e| format!("failed to read URL list row: {e}"))
+            })
+            .collect::<Result<Vec<Vec<String>>, String>>()?
+    } else {
+        trimmed
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.starts_with('#'))
+            .map(|line| line.split_whitespace().map(str::to_string).collect())
+            .collect()
+    };
+
+    let mut out = Vec::new();
+    for fields in rows {
+        let mut fields = fields.into_iter().filter(|field| !field.is_empty());
+        let Some(url) = fields.next() else {
+            continue;
+        };
+        let mut expectation = ListExpectation::default();
+        for field in fields {
+            match field.parse::<u16>() {
+                Ok(status) => expectation.status = Some(status),
+                Err(_) => expectation.redirect_target = Some(field),
+            }
+        }
+        out.push((url, expectation));
+    }
+    Ok(out)
+}
+
+/// Headerless CSV lines follow the URL with a status code or redirect target;
+/// any other comma is part of a whitespace-separated URL.
+fn is_csv_list_line(line: &str) -> bool {
+    line.starts_with('"')
+        || line.split(',').nth(1).is_some_and(|field| {
+            let field = field.trim().trim_matches('"');
+            field.parse::<u16>().is_ok() || field.contains("://") || field.starts_with('/')
+        })
+}
+
+fn load_url_list_csv(text: &str) -> Result<Vec<(String, ListExpectation)>, String> {
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .from_reader(text.as_bytes());
+    let headers = reader
+        .headers()
+        .map_err(|e| format!("failed to read URL list header: {e}"))?
+        .iter()
+        .map(|h| h.trim().to_ascii_lowercase())
+        .collect::<Vec<_>>();
+    let find = |names: &[&str]| {
+        names
+            .iter()
+            .find_map(|name| headers.iter().position(|h| h == name))
+    };
+    let url_idx = find(&["url", "address", "loc", "old_url", "source", "from"])
+        .ok_or_else(|| "URL list CSV needs a url, address or old_url column".to_string())?;
+    let status_idx = find(&["expected_status", "status"]);
+    let target_idx = find(&[
+        "expected_redirect",
+        "redirect_target",
+        "new_url",
+        "target",
+        "to",
+    ]);
+
+    let mut out = Vec::new();
+    for record in reader.records() {
+        let record = record.map_err(|e| format!("failed to read URL list row: {e}"))?;
+        let url = record.get(url_idx).unwrap_or_default().trim().to_string();
+        if url.is_empty() {
+            continue;
+        }
+        let expectation = ListExpectation {
+            status: status_idx
+                .and_then(|idx| record.get(idx))
+                .and_then(|value| value.trim().parse::<u16>().ok()),
+            redirect_target: target_idx
+                .and_then(|idx| record.get(idx))
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty()),
+        };
+        out.push((url, expectation));
+    }
+    Ok(out)
+}
+
+pub(crate) fn write_redirect_report(path: &str, checks: &[RedirectCheck]) -> io::Result<()> {
+    let mut writer = csv::Writer::from_path(path)?;
+    writer.write_record([
+        "old_url",
+        "expected_url",
+        "final_url",
+        "final_status",
+        "hop_count",
+        "hop_statuses",
+        "chain",
+        "outcome",
+    ])?;
+    for check in checks {
+        let hop_statuses = check
+            .hops
+            .iter()
+            .map(|(status, _)| status.to_string())
+            .collect::<Vec<_>>()
+            .join("|");
+        let chain = std::iter::once(check.old_url.as_str())
+            .chain(check.hops.iter().map(|(_, target)| target.as_str()))
+            .collect::<Vec<_>>()
+            .join(" -> ");
+        writer.write_record([
+            check.old_url.clone(),
+            check.expected_url.clone(),
+            check.final_url.clone(),
+            check
+                .final_status
+                .map(|s| s.to_string())
+                .unwrap_or_default(),
+            check.hops.len().to_string(),
+            hop_statuses,
+            chain,
+            check.outcome.label().to_string(),
+        ])?;
+    }
+    writer.flush()
+}
+
+/// Writes `<output>.frontier.txt` (one URL per line, usable with `--list`)
+/// and `<output>.meta.json` next to the export. Returns the frontier path.
+pub(crate) fn write_budget_sidecars(output_path: &str, stop: &BudgetStop) -> io::Result<String> {
+    let output = Path::new(output_path);
+    let frontier_path = output.with_extension("frontier.txt");
+    let meta_path = output.with_extension("meta.json");
+
+    let mut frontier = File::create(&frontier_path)?;
+    for url in &stop.frontier {
+        writeln!(frontier, "{url}")?;
+    }
+    frontier.flush()?;
+
+    let meta = json!({
+        "status": "stopped_by_budget",
+        "reason": stop.reason,
+        "pages": stop.pages,
+        "bytes": stop.bytes,
+        "elapsed_secs": stop.elapsed_secs,
+        "frontier_count": stop.frontier.len(),
+        "frontier_file": frontier_path.display().to_string(),
+        "output": output_path,
+        "finished_at": Utc::now().to_rfc3339(),
+    });
+    fs::write(
+        &meta_path,
+        serde_json::to_vec_pretty(&meta).map_err(io::Error::other)?,
+    )?;
+    Ok(frontier_path.display().to_string())
+}
+
+/// Writes `<output>.variants.csv`: each normalized URL with the raw forms
+/// (tracking/filtered parameters, case, trailing slash) that collapsed into it.
+pub(crate) fn write_url_variant_report(
+    output_path: &str,
+    variants: &[(String, Vec<String>)],
+) -> io::Result<String> {
+    let path = Path::new(output_path).with_extension("variants.csv");
+    let mut writer = csv::Writer::from_path(&path)?;
+    writer.write_record(["normalized_url", "variant_count", "variants"])?;
+    for (url, forms) in variants {
+        writer.write_record([url.clone(), forms.len().to_string(), forms.join("|")])?;
+    }
+    writer.flush()?;
+    Ok(path.display().to_string())
+}
+
+/// Writes `<output>.pagination.csv`: one line per reconstructed pagination
+/// sequence with its missing pages and link problems.
+pub(crate) fn write_pagination_report(
+    output_path: &str,
+    series: &[PaginationSeries],
+) -> io::Result<String> {
+    let path = Path::new(output_path).with_extension("pagination.csv");
+    let mut writer = csv::Writer::from_path(&path)?;
+    writer.write_record([
+        "series",
+        "pages",
+        "last_page",
+        "missing_pages",
+        "link_errors",
+        "canonical_to_first",
+        "status",
+    ])?;
+    for entry in series {
+        writer.write_record([
+            entry.key.clone(),
+            entry.pages.to_string(),
+            entry.last_page.to_string(),
+            entry
+                .missing_pages
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join("|"),
+            entry.link_errors.to_string(),
+            entry.canonical_to_first.to_string(),
+            entry.status().to_string(),
+        ])?;
+    }
+    writer.flush()?;
+    Ok(path.display().to_string())
+}
+
+/// Writes `<output>.issues.csv`: every issue found with its severity,
+/// category, penalty and page count, most severe first.
+pub(crate) fn write_issue_report(
+    output_path: &str,
+    issues: &[(SeoIssue, usize)],
+) -> io::Result<String> {
+    let path = Path::new(output_path).with_extension("issues.csv");
+    let mut writer = csv::Writer::from_path(&path)?;
+    writer.write_record(["issue", "severity", "category", "penalty", "pages"])?;
+    for (issue, pages) in issues {
+        writer.write_record([
+            issue.label().to_string(),
+            issue.severity().label().to_string(),
+            issue.category().label().to_string(),
+            issue.penalty().to_string(),
+            pages.to_string(),
+        ])?;
+    }
+    writer.flush()?;
+    Ok(path.display().to_string())
+}
+
+/// Writes `<output>.traps.csv`: each suspected crawl trap with the number of
+/// URLs held back and a few samples.
+pub(crate) fn write_trap_report(output_path: &str, traps: &[CrawlTrap]) -> io::Result<String> {
+    let path = Path::new(output_path).with_extension("traps.csv");
+    let mut writer = csv::Writer::from_path(&path)?;
+    writer.write_record(["reason", "pattern", "skipped_urls", "samples"])?;
+    for trap in traps {
+        writer.write_record([
+            trap.reason.label().to_string(),
+            trap.pattern.clone(),
+            trap.skipped.to_string(),
+            trap.samples.join("|"),
+        ])?;
+    }
+    writer.flush()?;
+    Ok(path.display().to_string())
+}
+
+pub(crate) fn load_login_script(path: &str) -> Result<LoginScript, String> {
+    let text =
+        fs::read_to_string(path).map_err(|e| format!("failed to read login script {path}: {e}"))?;
+    let script: LoginScript =
+        serde_json::from_str(&text).map_err(|e| format!("invalid login script {path}: {e}"))?;
+    if script.fields.is_empty() && script.submit.is_none() {
+        return Err(format!(
+            "login script {path} has no fields or submit selector"
+        ));
+    }
+    Ok(script)
+}
+
+pub(crate) fn load_rule_file(path: &str) -> Result<RuleFile, String> {
+    let text =
+        fs::read_to_string(path).map_err(|e| format!("failed to read rule file {path}: {e}"))?;
+    serde_json::from_str(&text).map_err(|e| format!("invalid rule file {path}: {e}"))
+}
+
+/// Headless progress goes to stderr unless `--progress-file` is set.
+pub(crate) fn open_progress_stream(path: Option<&str>) -> io::Result<Box<dyn Write>> {
+    match path {
+        Some(path) => {
+            let file = fs::File::create(path).map_err(|e| {
+                io::Error::new(
+                    e.kind(),
+                    format!("failed to create progress file {path}: {e}"),
+                )
+            })?;
+            Ok(Box::new(io::BufWriter::new(file)))
+        }
+        None => Ok(Box::new(io::stderr())),
+    }
+}
+
+pub(crate) fn default_output_path(url: &str, format: DataFormat) -> String {
     let host = Url::parse(url)
         .ok()
         .and_then(|u| u.host_str().map(|h| h.to_string()))
@@ -473,7 +1033,179 @@ fn default_output_path(url: &str, format: DataFormat) -> String {
 
 const MAX_FETCH_CONCURRENCY: usize = 256;
 
-fn sanitize_fetch_concurrency(value: usize) -> usize {
+pub(crate) fn sanitize_fetch_concurrency(value: usize) -> usize {
     value.max(1).min(MAX_FETCH_CONCURRENCY)
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn with_file<T>(name: &str, contents: &str, read: impl FnOnce(&str) -> T) -> T {
+        let path = std::env::temp_dir().join(format!("gh0st-{}-{name}", std::process::id()));
+        fs::write(&path, contents).unwrap();
+        let out = read(&path.to_string_lossy());
+        let _ = fs::remove_file(&path);
+        out
+    }
+
+    fn list(name: &str, contents: &str) -> Vec<(String, Option<u16>, Option<String>)> {
+        with_file(name, contents, |path| load_url_list(path).unwrap())
+            .into_iter()
+            .map(|(url, expectation)| (url, expectation.status, expectation.redirect_target))
+            .collect()
+    }
+
+    fn entry(
+        url: &str,
+        status: Option<u16>,
+        target: Option<&str>,
+    ) -> (String, Option<u16>, Option<String>) {
+        (url.to_string(), status, target.map(str::to_string))
+    }
+
+    #[test]
+    fn url_list_reads_sitemaps() {
+        let urls = list(
+            "list-sitemap.xml",
+            "\u{feff}<?xml version=\"1.0\"?>\n<urlset>\n  <url><loc>https://example.com/</loc></url>\n  <url><loc>https://example.com/a?x=1&amp;y=2</loc></url>\n</urlset>\n",
+        );
+        assert_eq!(
+            urls,
+            vec![
+                entry("https://example.com/", None, None),
+                entry("https://example.com/a?x=1&y=2", None, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn url_list_reads_csv_with_header() {
+        let urls = list(
+            "list-header.csv",
+            "Old_URL,Expected_Status,New_URL\nhttps://example.com/old,301,https://example.com/new\n\"https://example.com/a,b\",200,\n,404,\n",
+        );
+        assert_eq!(
+            urls,
+            vec![
+                entry(
+                    "https://example.com/old",
+                    Some(301),
+                    Some("https://example.com/new")
+                ),
+                entry("https://example.com/a,b", Some(200), None),
+            ]
+        );
+        let err = with_file("list-nourl.csv", "name,status\nhome,200\n", |path| {
+            load_url_list(path).unwrap_err()
+        });
+        assert!(err.contains("url"), "{err}");
+    }
+
+    #[test]
+    fn url_list_reads_headerless_csv() {
+        let urls = list(
+            "list-headerless.csv",
+            "https://example.com/old,301,https://example.com/new\n# moved\nhttps://example.com/gone,410\n\"https://example.com/a,b\",200\n",
+        );
+        assert_eq!(
+            urls,
+            vec![
+                entry(
+                    "https://example.com/old",
+                    Some(301),
+                    Some("https://example.com/new")
+                ),
+                entry("https://example.com/gone", Some(410), None),
+                entry("https://example.com/a,b", Some(200), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn url_list_reads_whitespace_lines() {
+        let urls = list(
+            "list-plain.txt",
+            "https://example.com/a,b\n\n# comment\nhttps://example.com/old 301 https://example.com/new\nhttps://example.com/c,d\t404\n",
+        );
+        assert_eq!(
+            urls,
+            vec![
+                entry("https://example.com/a,b", None, None),
+                entry(
+                    "https://example.com/old",
+                    Some(301),
+                    Some("https://example.com/new")
+                ),
+                entry("https://example.com/c,d", Some(404), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn cookie_jar_skips_expired_cookies() {
+        let future = Utc::now().timestamp() + 3600;
+        let past = Utc::now().timestamp() - 3600;
+        let jar = format!(
+            "# Netscape HTTP Cookie File\n\
+             example.com\tFALSE\t/\tTRUE\t{future}\tsession\tabc\n\
+             #HttpOnly_.example.com\tTRUE\t/\tTRUE\t0\ttoken\txyz\n\
+             example.com\tFALSE\t/\tFALSE\t{past}\told\tstale\n\
+             theme = dark\n"
+        );
+        let cookies = with_file("cookies.txt", &jar, |path| load_cookie_jar(path).unwrap());
+        assert_eq!(
+            cookies,
+            vec![
+                (
+                    "example.com".to_string(),
+                    "session".to_string(),
+                    "abc".to_string()
+                ),
+                (
+                    ".example.com".to_string(),
+                    "token".to_string(),
+                    "xyz".to_string()
+                ),
+                (String::new(), "theme".to_string(), "dark".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn exports_keep_custom_issue_definitions() {
+        let custom = SeoIssue::custom_with(
+            "data_io_test_custom",
+            12,
+            IssueSeverity::Error,
+            IssueCategory::Security,
+        );
+        let mut row = CrawlRow::not_retrieved("https://example.com/", "timeout");
+        row.issues.push(custom.clone());
+        for (name, format) in [
+            ("custom.csv", DataFormat::Csv),
+            ("custom.json", DataFormat::Json),
+        ] {
+            let loaded = with_file(name, "", |path| {
+                let mut sink = OutputSink::new(path, format).unwrap();
+                sink.write_row(&row, &[]).unwrap();
+                sink.finalize().unwrap();
+                drop(sink);
+                load_rows_from_file(path).unwrap()
+            });
+            let issues = &loaded[0].0.issues;
+            assert_eq!(issues, &row.issues, "{name}");
+            assert_eq!(issues[1].penalty(), 12);
+            assert_eq!(issues[1].severity(), IssueSeverity::Error);
+            assert_eq!(issues[1].category(), IssueCategory::Security);
+        }
+
+        let mut old = row_to_export_record(&row, &[]);
+        old.custom_issues.clear();
+        let (reloaded, _) = export_record_to_row(old);
+        assert_eq!(
+            reloaded.issues[1],
+            SeoIssue::custom("data_io_test_custom", 0)
+        );
+    }
+}
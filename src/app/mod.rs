@@ -1,4 +1,4 @@
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
 use std::fs::{self, File};
 use std::io::{self, Stdout, Write};
 use std::net::{TcpListener, TcpStream, ToSocketAddrs};
@@ -8,8 +8,10 @@ use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::time::{Duration, Instant};
 
+use base64::Engine;
 use chrono::Utc;
-use clap::{ArgAction, Parser, ValueEnum};
+use clap::parser::ValueSource;
+use clap::{ArgAction, ArgMatches, CommandFactory, FromArgMatches, Parser, ValueEnum};
 use crossterm::event::{
     self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyModifiers, MouseButton,
     MouseEventKind,
@@ -33,13 +35,39 @@ use spider::ClientBuilder;
 use spider::page::Page;
 use spider::website::Website;
 use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::mpsc::error::TryRecvError;
 use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
 use tokio::task::JoinSet;
 use url::Url;
 
-include!("types.rs");
-include!("data_io.rs");
-include!("runtime.rs");
-include!("tui.rs");
-include!("crawl.rs");
-include!("ui_utils.rs");
+mod analyzers;
+mod api;
+mod crawl;
+mod data_io;
+mod rules;
+mod runtime;
+mod server;
+mod store;
+mod tui;
+mod types;
+mod ui_utils;
+
+use analyzers::*;
+use crawl::*;
+use data_io::*;
+use rules::*;
+use runtime::*;
+use server::*;
+use store::*;
+use tui::*;
+use types::*;
+use ui_utils::*;
+
+pub use analyzers::{Analyzer, AnalyzerOutput, AnalyzerRegistry, PageContext};
+pub use api::{CrawlBuilder, CrawlHandle, analyze_html};
+pub use data_io::{CsvSink, JsonSink, OutputSink, detect_data_format, load_rows_from_file};
+pub use runtime::run;
+pub use types::{
+    BudgetStop, CrawlControl, CrawlEvent, CrawlRow, CrawlTrap, CustomIssue, DataFormat, FacetCap,
+    HostRate, IssueCategory, IssueSeverity, RetryScope, SeoIssue, TrapReason,
+};
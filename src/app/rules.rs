@@ -0,0 +1,511 @@
+use super::*;
+
+/// A rule file loaded from `--rules`: `{"rules": [...]}`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct RuleFile {
+    rules: Vec<RuleSpec>,
+}
+
+/// One rule as written in the file. The rule applies to pages matching every
+/// `when` condition and raises its issue when one of the `require`
+/// conditions fails, or unconditionally when `require` is empty.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RuleSpec {
+    label: String,
+    #[serde(default)]
+    severity: Option<String>,
+    #[serde(default)]
+    category: Option<String>,
+    #[serde(default)]
+    penalty: Option<u8>,
+    #[serde(default)]
+    when: Vec<String>,
+    #[serde(default)]
+    require: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RuleOp {
+    Eq,
+    Ne,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+    Contains,
+    NotContains,
+    Matches,
+    NotMatches,
+    Exists,
+    Missing,
+}
+
+impl RuleOp {
+    fn parse(op: &str) -> Option<Self> {
+        Some(match op {
+            "==" | "=" => RuleOp::Eq,
+            "!=" => RuleOp::Ne,
+            ">" => RuleOp::Gt,
+            ">=" => RuleOp::Ge,
+            "<" => RuleOp::Lt,
+            "<=" => RuleOp::Le,
+            "contains" => RuleOp::Contains,
+            "!contains" => RuleOp::NotContains,
+            "matches" => RuleOp::Matches,
+            "!matches" => RuleOp::NotMatches,
+            "exists" => RuleOp::Exists,
+            "missing" => RuleOp::Missing,
+            _ => return None,
+        })
+    }
+
+    fn is_unary(self) -> bool {
+        matches!(self, RuleOp::Exists | RuleOp::Missing)
+    }
+}
+
+/// `FIELD OP VALUE`, e.g. `word_count > 200` or `url matches */p/*`.
+#[derive(Debug, Clone)]
+struct RuleCondition {
+    field: String,
+    op: RuleOp,
+    value: String,
+}
+
+impl RuleCondition {
+    fn parse(text: &str) -> Result<Self, String> {
+        let mut parts = text.trim().splitn(3, char::is_whitespace);
+        let field = parts.next().unwrap_or("").to_ascii_lowercase();
+        let op_text = parts.next().unwrap_or("").trim();
+        let value = parts.next().unwrap_or("").trim();
+        let op = RuleOp::parse(op_text)
+            .ok_or_else(|| format!("condition '{text}': unknown operator '{op_text}'"))?;
+        if field.is_empty() || (!op.is_unary() && value.is_empty()) {
+            return Err(format!("condition '{text}': expected FIELD OP VALUE"));
+        }
+        if !is_rule_field(&field) {
+            return Err(format!("condition '{text}': unknown field '{field}'"));
+        }
+        let value = value
+            .strip_prefix('"')
+            .and_then(|v| v.strip_suffix('"'))
+            .unwrap_or(value);
+        Ok(RuleCondition {
+            field,
+            op,
+            value: value.to_string(),
+        })
+    }
+
+    fn holds(&self, actual: &str) -> bool {
+        let actual = actual.trim();
+        let number = || {
+            actual
+                .parse::<f64>()
+                .ok()
+                .zip(self.value.parse::<f64>().ok())
+        };
+        let lower = actual.to_ascii_lowercase();
+        let expected = self.value.to_ascii_lowercase();
+        match self.op {
+            RuleOp::Eq => number().map_or(lower == expected, |(a, b)| a == b),
+            RuleOp::Ne => number().map_or(lower != expected, |(a, b)| a != b),
+            RuleOp::Gt => number().is_some_and(|(a, b)| a > b),
+            RuleOp::Ge => number().is_some_and(|(a, b)| a >= b),
+            RuleOp::Lt => number().is_some_and(|(a, b)| a < b),
+            RuleOp::Le => number().is_some_and(|(a, b)| a <= b),
+            RuleOp::Contains => lower.contains(&expected),
+            RuleOp::NotContains => !lower.contains(&expected),
+            RuleOp::Matches => wildcard_match(&expected, &lower),
+            RuleOp::NotMatches => !wildcard_match(&expected, &lower),
+            RuleOp::Exists => !actual.is_empty(),
+            RuleOp::Missing => actual.is_empty(),
+        }
+    }
+}
+
+/// Export columns, the derived `path`, `host` and `schema_types`, and
+/// `header.<name>` / `extra.<name>`.
+fn is_rule_field(field: &str) -> bool {
+    let prefixed = |prefix: &str| {
+        field
+            .strip_prefix(prefix)
+            .is_some_and(|name| !name.is_empty())
+    };
+    CSV_HEADERS.contains(&field)
+        || matches!(field, "path" | "host" | "schema_types")
+        || prefixed("header.")
+        || prefixed("extra.")
+}
+
+/// `*` matches any run of characters; everything else is literal.
+pub(crate) fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or("");
+    let Some(mut rest) = text.strip_prefix(first) else {
+        return false;
+    };
+    let mut parts = parts.collect::<Vec<_>>();
+    let Some(last) = parts.pop() else {
+        return rest.is_empty();
+    };
+    for part in parts {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    rest.len() >= last.len() && rest.ends_with(last)
+}
+
+#[derive(Debug, Clone)]
+struct Rule {
+    issue: SeoIssue,
+    when: Vec<RuleCondition>,
+    require: Vec<RuleCondition>,
+}
+
+impl Rule {
+    fn from_spec(spec: RuleSpec) -> Result<Self, String> {
+        let label = spec.label.trim();
+        if label.is_empty() || label.contains(['|', ',']) || label.contains(char::is_whitespace) {
+            return Err(format!(
+                "rule label '{label}' must be non-empty without spaces, '|' or ','"
+            ));
+        }
+        if SeoIssue::from_label(label).is_some() {
+            return Err(format!("rule label '{label}' is a built-in issue"));
+        }
+        let severity = match spec.severity.as_deref() {
+            Some(severity) => IssueSeverity::from_label(severity)
+                .ok_or_else(|| format!("rule '{label}': unknown severity '{severity}'"))?,
+            None => IssueSeverity::Warning,
+        };
+        let category = match spec.category.as_deref() {
+            Some(category) => IssueCategory::from_label(category)
+                .ok_or_else(|| format!("rule '{label}': unknown category '{category}'"))?,
+            None => IssueCategory::Content,
+        };
+        let penalty = spec.penalty.unwrap_or(severity.default_penalty()).min(100);
+        let parse = |conditions: &[String]| {
+            conditions
+                .iter()
+                .map(|condition| {
+                    RuleCondition::parse(condition).map_err(|e| format!("rule '{label}': {e}"))
+                })
+                .collect::<Result<Vec<_>, _>>()
+        };
+        Ok(Rule {
+            issue: SeoIssue::custom_with(label, penalty, severity, category),
+            when: parse(&spec.when)?,
+            require: parse(&spec.require)?,
+        })
+    }
+}
+
+/// Evaluates `--rules` against each page: export columns (`url`,
+/// `word_count`, `issues`, ...), fields from earlier analyzers, plus `path`,
+/// `host`, `schema_types` and `header.<name>`.
+pub(crate) struct RuleAnalyzer {
+    rules: Vec<Rule>,
+}
+
+impl RuleAnalyzer {
+    pub(crate) fn from_file(path: &str) -> Result<Self, String> {
+        let rules = load_rule_file(path)?
+            .rules
+            .into_iter()
+            .map(Rule::from_spec)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("{path}: {e}"))?;
+        // Several rules may raise one issue, but only with one definition.
+        let mut issues = HashMap::<&str, SeoIssue>::new();
+        for rule in &rules {
+            let label = rule.issue.label();
+            if issues
+                .insert(label, rule.issue.clone())
+                .is_some_and(|known| known != rule.issue)
+            {
+                return Err(format!(
+                    "{path}: rules for '{label}' disagree on severity, category or penalty"
+                ));
+            }
+        }
+        Ok(RuleAnalyzer { rules })
+    }
+}
+
+impl Analyzer for RuleAnalyzer {
+    fn name(&self) -> &str {
+        "rules"
+    }
+
+    fn analyze(&self, page: &PageContext<'_>, out: &mut AnalyzerOutput) {
+        let mut record = match serde_json::to_value(row_to_export_record(page.row, &[])) {
+            Ok(Value::Object(record)) => record,
+            _ => return,
+        };
+        // Issues raised so far by the other analyzers, not the stale row list.
+        record.insert(
+            "issues".to_string(),
+            Value::String(issues_to_csv(&out.issues)),
+        );
+        record.insert("issue_count".to_string(), Value::from(out.issues.len()));
+        let mut schema_types = None;
+        let mut lookup = |field: &str| -> String {
+            if let Some(name) = field.strip_prefix("header.") {
+                return page.header(name).unwrap_or("").to_string();
+            }
+            match field {
+                "path" | "host" => {
+                    let parsed = Url::parse(page.url).ok();
+                    return parsed
+                        .as_ref()
+                        .and_then(|u| {
+                            if field == "path" {
+                                Some(u.path())
+                            } else {
+                                u.host_str()
+                            }
+                        })
+                        .unwrap_or("")
+                        .to_string();
+                }
+                "schema_types" => {
+                    return schema_types
+                        .get_or_insert_with(|| {
+                            page.document.map(extract_schema_types).unwrap_or_default()
+                        })
+                        .clone();
+                }
+                _ => {}
+            }
+            if let Some(name) = field.strip_prefix("extra.") {
+                return out.fields.get(name).cloned().unwrap_or_default();
+            }
+            match record.get(field) {
+                Some(Value::String(value)) => value.clone(),
+                Some(Value::Null) | None => String::new(),
+                Some(value) => value.to_string(),
+            }
+        };
+
+        let mut raised = Vec::new();
+        for rule in &self.rules {
+            let applies = rule
+                .when
+                .iter()
+                .all(|condition| condition.holds(&lookup(&condition.field)));
+            let fails = rule.require.is_empty()
+                || rule
+                    .require
+                    .iter()
+                    .any(|condition| !condition.holds(&lookup(&condition.field)));
+            if applies && fails {
+                raised.push(rule.issue.clone());
+            }
+        }
+        out.issues.extend(raised);
+    }
+}
+
+/// Schema.org types declared on the page through JSON-LD `@type` or
+/// microdata `itemtype`, `|`-separated.
+fn extract_schema_types(doc: &Html) -> String {
+    fn collect(value: &Value, types: &mut Vec<String>) {
+        match value {
+            Value::Array(items) => items.iter().for_each(|item| collect(item, types)),
+            Value::Object(object) => {
+                match object.get("@type") {
+                    Some(Value::String(kind)) => types.push(kind.clone()),
+                    Some(Value::Array(kinds)) => types.extend(
+                        kinds
+                            .iter()
+                            .filter_map(Value::as_str)
+                            .map(ToString::to_string),
+                    ),
+                    _ => {}
+                }
+                object.values().for_each(|item| collect(item, types));
+            }
+            _ => {}
+        }
+    }
+
+    let mut types = Vec::new();
+    if let Ok(selector) = Selector::parse("script[type=\"application/ld+json\"]") {
+        for script in doc.select(&selector) {
+            if let Ok(value) = serde_json::from_str::<Value>(&script.text().collect::<String>()) {
+                collect(&value, &mut types);
+            }
+        }
+    }
+    if let Ok(selector) = Selector::parse("[itemtype]") {
+        types.extend(doc.select(&selector).flat_map(|el| {
+            el.value()
+                .attr("itemtype")
+                .unwrap_or("")
+                .split_whitespace()
+                .map(|kind| kind.rsplit('/').next().unwrap_or(kind).to_string())
+                .collect::<Vec<_>>()
+        }));
+    }
+    let mut seen = HashSet::new();
+    types.retain(|kind| seen.insert(kind.clone()));
+    types.join("|")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wildcard_match_anchors_both_ends() {
+        assert!(wildcard_match("*", ""));
+        assert!(wildcard_match("*", "anything"));
+        assert!(wildcard_match("", ""));
+        assert!(!wildcard_match("", "x"));
+        assert!(wildcard_match("/blog/*", "/blog/post"));
+        assert!(!wildcard_match("/blog/*", "/news/blog/post"));
+        assert!(wildcard_match("*.pdf", "/files/a.pdf"));
+        assert!(!wildcard_match("*.pdf", "/files/a.pdf?x=1"));
+        assert!(wildcard_match("*/p/*", "https://shop.example.com/p/42"));
+        assert!(wildcard_match("a*b*c", "axxbyyc"));
+        assert!(!wildcard_match("a*b*c", "axxcyyb"));
+    }
+
+    #[test]
+    fn wildcard_match_does_not_reuse_characters() {
+        // The prefix and suffix may not overlap in the text.
+        assert!(!wildcard_match("a*a", "a"));
+        assert!(wildcard_match("a*a", "aa"));
+        assert!(!wildcard_match("ab*b", "ab"));
+        assert!(wildcard_match("ab*b", "abb"));
+        assert!(wildcard_match("**", "x"));
+    }
+
+    #[test]
+    fn condition_parses_operators() {
+        for (text, op) in [
+            ("status == 200", RuleOp::Eq),
+            ("status = 200", RuleOp::Eq),
+            ("status != 200", RuleOp::Ne),
+            ("word_count > 200", RuleOp::Gt),
+            ("word_count >= 200", RuleOp::Ge),
+            ("word_count < 200", RuleOp::Lt),
+            ("word_count <= 200", RuleOp::Le),
+            ("title contains shop", RuleOp::Contains),
+            ("title !contains shop", RuleOp::NotContains),
+            ("url matches */p/*", RuleOp::Matches),
+            ("url !matches */p/*", RuleOp::NotMatches),
+            ("canonical exists", RuleOp::Exists),
+            ("canonical missing", RuleOp::Missing),
+        ] {
+            let condition = RuleCondition::parse(text).unwrap();
+            assert_eq!(condition.op, op, "{text}");
+        }
+    }
+
+    #[test]
+    fn condition_keeps_quoted_values_and_lowercases_fields() {
+        let condition = RuleCondition::parse(r#"Title == "Summer Sale""#).unwrap();
+        assert_eq!(condition.field, "title");
+        assert_eq!(condition.value, "Summer Sale");
+        assert!(condition.holds("summer sale"));
+
+        let condition = RuleCondition::parse("title contains a  b").unwrap();
+        assert_eq!(condition.value, "a  b");
+
+        let condition = RuleCondition::parse(r#"title == """#).unwrap();
+        assert_eq!(condition.value, "");
+    }
+
+    #[test]
+    fn condition_rejects_malformed_text() {
+        for text in [
+            "",
+            "status",
+            "status ~ 200",
+            "status ==",
+            "wordcount > 200",
+            "header. exists",
+            "extra. missing",
+        ] {
+            assert!(RuleCondition::parse(text).is_err(), "{text}");
+        }
+        assert!(RuleCondition::parse("header.x-robots-tag contains noindex").is_ok());
+        assert!(RuleCondition::parse("extra.author missing").is_ok());
+        assert!(RuleCondition::parse("schema_types contains Product").is_ok());
+    }
+
+    #[test]
+    fn condition_compares_numbers_and_wildcards() {
+        let gt = RuleCondition::parse("word_count > 200").unwrap();
+        assert!(gt.holds("201"));
+        assert!(!gt.holds("200"));
+        assert!(!gt.holds("many"));
+        assert!(
+            RuleCondition::parse("status == 200")
+                .unwrap()
+                .holds("200.0")
+        );
+        let matches = RuleCondition::parse("url matches */P/*").unwrap();
+        assert!(matches.holds("https://example.com/p/1"));
+        assert!(!matches.holds("https://example.com/c/1"));
+        let missing = RuleCondition::parse("canonical missing").unwrap();
+        assert!(missing.holds("  "));
+        assert!(!missing.holds("https://example.com/"));
+    }
+
+    #[test]
+    fn rule_file_rejects_unknown_keys() {
+        let typo = r#"{"rules": [{"label": "x", "requires": ["status == 200"]}]}"#;
+        assert!(serde_json::from_str::<RuleFile>(typo).is_err());
+        let top = r#"{"rules": [], "version": 2}"#;
+        assert!(serde_json::from_str::<RuleFile>(top).is_err());
+        let ok = r#"{"rules": [{"label": "x", "require": ["status == 200"]}]}"#;
+        assert!(serde_json::from_str::<RuleFile>(ok).is_ok());
+    }
+
+    #[test]
+    fn rule_specs_validate_labels_and_levels() {
+        let spec = |json: &str| serde_json::from_str::<RuleSpec>(json).unwrap();
+        assert!(Rule::from_spec(spec(r#"{"label": "title_too_short"}"#)).is_err());
+        assert!(Rule::from_spec(spec(r#"{"label": "two words"}"#)).is_err());
+        assert!(Rule::from_spec(spec(r#"{"label": "x", "severity": "fatal"}"#)).is_err());
+        assert!(Rule::from_spec(spec(r#"{"label": "x", "when": ["titel exists"]}"#)).is_err());
+        let rule = Rule::from_spec(spec(
+            r#"{"label": "rules_test_notice", "severity": "notice"}"#,
+        ))
+        .unwrap();
+        assert_eq!(rule.issue.severity(), IssueSeverity::Notice);
+        assert_eq!(rule.issue.penalty(), 3);
+    }
+
+    #[test]
+    fn rule_file_rejects_conflicting_definitions() {
+        let path = std::env::temp_dir().join(format!("gh0st-rules-{}.json", std::process::id()));
+        let write = |json: &str| fs::write(&path, json).unwrap();
+        let path_str = path.to_string_lossy().to_string();
+
+        write(
+            r#"{"rules": [
+                {"label": "rules_test_same", "penalty": 4, "when": ["status == 404"]},
+                {"label": "rules_test_same", "penalty": 4, "when": ["status == 410"]}
+            ]}"#,
+        );
+        assert_eq!(RuleAnalyzer::from_file(&path_str).unwrap().rules.len(), 2);
+
+        write(
+            r#"{"rules": [
+                {"label": "rules_test_conflict", "penalty": 4},
+                {"label": "rules_test_conflict", "penalty": 9}
+            ]}"#,
+        );
+        let err = RuleAnalyzer::from_file(&path_str).err().unwrap();
+        assert!(err.contains("rules_test_conflict"), "{err}");
+        let _ = fs::remove_file(&path);
+    }
+}
@@ -1,8 +1,10 @@
-fn current_fetch_concurrency(concurrency: &Arc<AtomicUsize>) -> usize {
+use super::*;
+
+pub(crate) fn current_fetch_concurrency(concurrency: &Arc<AtomicUsize>) -> usize {
     sanitize_fetch_concurrency(concurrency.load(Ordering::Relaxed))
 }
 
-fn handle_crawl_event(
+pub(crate) fn handle_crawl_event(
     state: &mut AppState,
     sink: Option<&mut OutputSink>,
     event: CrawlEvent,
@@ -12,7 +14,7 @@ fn handle_crawl_event(
             row,
             discovered_links,
         } => {
-            if state.push_row(row.clone(), discovered_links.clone()) {
+            if state.push_row(&row, &discovered_links) {
                 if let Some(sink) = sink {
                     sink.write_row(&row, &discovered_links)?;
                 }
@@ -20,7 +22,7 @@ fn handle_crawl_event(
         }
         CrawlEvent::Unretrieved { url, reason } => {
             let row = unretrieved_row(url, reason);
-            if state.push_row(row.clone(), Vec::new()) {
+            if state.push_row(&row, &[]) {
                 if let Some(sink) = sink {
                     sink.write_row(&row, &[])?;
                 }
@@ -29,9 +31,86 @@ fn handle_crawl_event(
         CrawlEvent::Stats { discovered } => {
             state.discovered_targets = state.discovered_targets.max(discovered);
         }
-        CrawlEvent::Finished => state.done = true,
+        CrawlEvent::Finished => {
+            state.done = true;
+            state.finished_runs += 1;
+            state.frontier.finish();
+            let issues = state.top_issues(usize::MAX);
+            if let Some(sink) = sink.as_deref()
+                && !state.issue_counts.is_empty()
+                && let Err(err) = write_issue_report(sink.path(), &issues)
+            {
+                state.push_error(format!("failed to save issue report: {err}"));
+            }
+            let series = state.pagination_report();
+            if let Some(sink) = sink
+                && !series.is_empty()
+            {
+                match write_pagination_report(sink.path(), &series) {
+                    Ok(path) => state.push_status(format!(
+                        "{} pagination sequences ({} with problems); report in {path}",
+                        series.len(),
+                        series.iter().filter(|entry| entry.status() != "ok").count()
+                    )),
+                    Err(err) => {
+                        state.push_error(format!("failed to save pagination report: {err}"))
+                    }
+                }
+            }
+        }
         CrawlEvent::Status(message) => state.push_status(message),
         CrawlEvent::Error(err) => state.push_error(err),
+        CrawlEvent::HostRates(rates) => state.host_rates = rates,
+        CrawlEvent::BudgetExhausted(stop) => {
+            let mut summary = format!("{} after {} pages", stop.reason, stop.pages);
+            if let Some(sink) = sink {
+                match write_budget_sidecars(sink.path(), &stop) {
+                    Ok(path) => {
+                        summary.push_str(&format!("; {} URLs left in {path}", stop.frontier.len()))
+                    }
+                    Err(err) => state.push_error(format!("failed to save frontier: {err}")),
+                }
+            }
+            state.push_status(format!("Stopped by budget: {summary}"));
+            state.budget_stop = Some(summary);
+        }
+        CrawlEvent::UrlVariants(variants) => {
+            let forms = variants.iter().map(|(_, forms)| forms.len()).sum::<usize>();
+            let mut summary = format!(
+                "URL normalization collapsed {forms} variants into {} pages",
+                variants.len()
+            );
+            if let Some(sink) = sink {
+                match write_url_variant_report(sink.path(), &variants) {
+                    Ok(path) => summary.push_str(&format!("; report in {path}")),
+                    Err(err) => state.push_error(format!("failed to save URL variants: {err}")),
+                }
+            }
+            state.push_status(summary);
+        }
+        CrawlEvent::FacetCaps(caps) => {
+            state.push_status(format!(
+                "Faceted navigation capped on {} paths; {} parameter combinations skipped",
+                caps.len(),
+                caps.iter().map(|cap| cap.dropped).sum::<usize>()
+            ));
+            state.facet_caps = caps;
+        }
+        CrawlEvent::CrawlTraps(traps) => {
+            let mut summary = format!(
+                "{} suspected crawl traps; {} URLs not queued",
+                traps.len(),
+                traps.iter().map(|trap| trap.skipped).sum::<usize>()
+            );
+            if let Some(sink) = sink {
+                match write_trap_report(sink.path(), &traps) {
+                    Ok(path) => summary.push_str(&format!("; report in {path}")),
+                    Err(err) => state.push_error(format!("failed to save trap report: {err}")),
+                }
+            }
+            state.push_status(summary);
+            state.crawl_traps = traps;
+        }
     }
 
     Ok(())
@@ -46,22 +125,42 @@ pub async fn run() -> io::Result<()> {
         let (tx, mut rx) = mpsc::unbounded_channel::<CrawlEvent>();
         for (row, outgoing_links) in review_rows {
             let _ = tx.send(CrawlEvent::Page {
-                row,
+                row: Box::new(row),
                 discovered_links: outgoing_links,
             });
         }
         let _ = tx.send(CrawlEvent::Finished);
         drop(tx);
 
+        let state = new_app_state(cli.state_dir.as_deref())?;
         if no_tui {
-            return run_review_headless(&review_file, &mut rx);
+            return run_review_headless(&review_file, state, &mut rx);
         }
-        return run_tui(&review_file, None, 1, None, auto_close, &mut rx);
+        let state = SharedState::new(std::sync::Mutex::new(state));
+        lock_state(&state).fetch_concurrency = 1;
+        return run_tui(&review_file, None, state, None, auto_close, &mut rx);
+    }
+
+    if let Some(mapping) = cli.verify_redirects.clone() {
+        return run_redirect_verification(&cli, &mapping).await;
     }
 
+    let analyzers = AnalyzerRegistry::for_crawl(&cli, &[])
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+    let list = cli
+        .list
+        .as_deref()
+        .map(load_url_list)
+        .transpose()
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?;
     let start_url = cli
         .url
-        .as_deref()
+        .first()
+        .or(list
+            .as_ref()
+            .and_then(|entries| entries.first().map(|(url, _)| url)))
+        .map(String::as_str)
+        .or(list.as_ref().map(|_| ""))
         .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "missing URL"))?;
     let configured_format: DataFormat = cli.format.into();
     let output_format = cli
@@ -74,18 +173,55 @@ pub async fn run() -> io::Result<()> {
         .clone()
         .unwrap_or_else(|| default_output_path(start_url, output_format));
 
+    let progress = if no_tui {
+        Some(open_progress_stream(cli.progress_file.as_deref())?)
+    } else {
+        None
+    };
+    let progress_format = cli.progress_format;
+
     let (tx, mut rx) = mpsc::unbounded_channel::<CrawlEvent>();
-    let initial_fetch_concurrency = sanitize_fetch_concurrency(cli.fetch_concurrency);
+    let state = SharedState::new(std::sync::Mutex::new(new_app_state(
+        cli.state_dir.as_deref(),
+    )?));
+    lock_state(&state).fetch_concurrency = sanitize_fetch_concurrency(cli.fetch_concurrency);
     let (control_tx, control_rx) = mpsc::unbounded_channel::<CrawlControl>();
-    let crawl_handle = tokio::spawn(run_crawler(cli, tx, control_rx));
-    let tui_result = if no_tui {
-        drop(control_tx);
-        run_headless(&output_path, output_format, &mut rx)
+    if let Some(addr) = cli.api.as_deref() {
+        let bound = start_control_api(addr, state.clone(), Some(control_tx.clone()))?;
+        let _ = tx.send(CrawlEvent::Status(format!(
+            "Control API listening on http://{bound}"
+        )));
+        if let Some(warning) = exposed_api_warning(bound) {
+            let _ = tx.send(CrawlEvent::Status(warning));
+        }
+    }
+    if let Some(addr) = cli.metrics.as_deref() {
+        let bound = start_control_api(addr, state.clone(), None)?;
+        let _ = tx.send(CrawlEvent::Status(format!(
+            "Metrics listening on http://{bound}/metrics"
+        )));
+    }
+    let frontier = lock_state(&state).frontier.clone();
+    let crawl_handle = tokio::spawn(run_crawler(cli, analyzers, list, frontier, tx, control_rx));
+    let api_state = state.clone();
+    let tui_result = if let Some(mut progress) = progress {
+        let result = run_headless(
+            &output_path,
+            output_format,
+            state,
+            progress_format,
+            &mut progress,
+            &mut rx,
+        );
+        // The crawler waits for retry commands while a control sender is
+        // alive, and the API keeps one.
+        let _ = control_tx.send(CrawlControl::Shutdown);
+        result
     } else {
         run_tui(
             &output_path,
             Some(control_tx),
-            initial_fetch_concurrency,
+            state,
             Some((output_path.as_str(), output_format)),
             auto_close,
             &mut rx,
@@ -95,6 +231,9 @@ pub async fn run() -> io::Result<()> {
     if let Err(e) = crawl_handle.await {
         eprintln!("crawler task join error: {e}");
     }
+    // API threads keep the state alive until exit; drop the rows and any
+    // spill file now.
+    lock_state(&api_state).rows = RowStore::default();
 
     tui_result
 }
@@ -102,43 +241,343 @@ pub async fn run() -> io::Result<()> {
 fn run_headless(
     output_path: &str,
     output_format: DataFormat,
+    shared_state: SharedState,
+    progress_format: ProgressFormatArg,
+    progress: &mut dyn Write,
     rx: &mut UnboundedReceiver<CrawlEvent>,
 ) -> io::Result<()> {
+    let json = progress_format == ProgressFormatArg::Json;
     let mut sink = OutputSink::new(output_path, output_format)?;
-    let mut state = AppState::default();
     loop {
-        while let Ok(event) = rx.try_recv() {
-            match &event {
-                CrawlEvent::Status(message) => eprintln!("{message}"),
-                CrawlEvent::Error(err) => eprintln!("{err}"),
-                _ => {}
+        let mut rows = Vec::new();
+        let mut lines = Vec::new();
+        let finished = {
+            let mut state = lock_state(&shared_state);
+            let mut disconnected = false;
+            loop {
+                let event = match rx.try_recv() {
+                    Ok(event) => event,
+                    Err(TryRecvError::Empty) => break,
+                    Err(TryRecvError::Disconnected) => {
+                        disconnected = true;
+                        break;
+                    }
+                };
+                let line = if json {
+                    progress_event_json(&event)
+                } else {
+                    match &event {
+                        CrawlEvent::Status(message) | CrawlEvent::Error(message) => {
+                            Some(Value::String(message.clone()))
+                        }
+                        _ => None,
+                    }
+                };
+                match event {
+                    CrawlEvent::Page {
+                        row,
+                        discovered_links,
+                    } => {
+                        if state.push_row(&row, &discovered_links) {
+                            rows.push((row, discovered_links));
+                        }
+                    }
+                    CrawlEvent::Unretrieved { url, reason } => {
+                        let row = unretrieved_row(url, reason);
+                        if state.push_row(&row, &[]) {
+                            rows.push((Box::new(row), Vec::new()));
+                        }
+                    }
+                    // The rest only write a sidecar report once per run.
+                    event => handle_crawl_event(&mut state, Some(&mut sink), event)?,
+                }
+                match line {
+                    Some(Value::Object(mut object)) => {
+                        object.insert("parsed".to_string(), json!(state.parsed));
+                        object.insert("discovered".to_string(), json!(state.discovered_total()));
+                        lines.push(Value::Object(object).to_string());
+                    }
+                    Some(Value::String(message)) => lines.push(message),
+                    _ => {}
+                }
             }
-            handle_crawl_event(&mut state, Some(&mut sink), event)?;
-        }
+            // Retries queued through the API run after the crawl finished and
+            // finish once more each; so do added URLs no crawl loop took up.
+            let finished = disconnected
+                || (state.done
+                    && state.finished_runs + state.frontier.merged_batches()
+                        > state.queued_retries);
+            state.closing = finished;
+            finished
+        };
 
+        // Written without the lock so a slow disk or stdout reader does not
+        // stall the API.
+        for (row, discovered_links) in &rows {
+            sink.write_row(row, discovered_links)?;
+        }
+        for line in &lines {
+            writeln!(progress, "{line}")?;
+        }
         sink.flush()?;
-        if state.done {
+        progress.flush()?;
+        if finished {
             break;
         }
         std::thread::sleep(Duration::from_millis(120));
     }
-
     sink.finalize()?;
-    eprintln!(
+    // Formatted under the lock and written without it.
+    let mut summary = Vec::new();
+    write_headless_summary(&mut summary, &lock_state(&shared_state), json, output_path)?;
+    progress.write_all(&summary)?;
+    progress.flush()
+}
+
+fn write_headless_summary(
+    out: &mut dyn Write,
+    state: &AppState,
+    json: bool,
+    output_path: &str,
+) -> io::Result<()> {
+    let severities = state.severity_page_counts();
+    let series = state.pagination_report();
+    if json {
+        writeln!(
+            out,
+            "{}",
+            json!({
+                "event": "finished",
+                "time": Utc::now().to_rfc3339(),
+                "parsed": state.parsed,
+                "discovered": state.discovered_total(),
+                "average_seo_score": state.average_seo_score(),
+                "output": output_path,
+                "budget_stop": state.budget_stop,
+                "pages_with_issues": severities
+                    .iter()
+                    .map(|(severity, pages)| (severity.label().to_string(), json!(pages)))
+                    .collect::<serde_json::Map<_, _>>(),
+                "pagination_sequences": series.len(),
+                "pagination_problems": series
+                    .iter()
+                    .filter(|entry| entry.status() != "ok")
+                    .count(),
+                "capped_facet_paths": state.facet_caps.len(),
+                "crawl_traps": state.crawl_traps.len(),
+            })
+        )?;
+        return Ok(());
+    }
+    writeln!(
+        out,
         "finished crawl: parsed={} discovered={} avg_score={} output={}",
         state.parsed,
         state.discovered_total(),
         state.average_seo_score(),
         output_path
+    )?;
+    if let Some(summary) = state.budget_stop.as_deref() {
+        writeln!(out, "stopped by budget: {summary}")?;
+    }
+    if severities.iter().any(|(_, pages)| *pages > 0) {
+        writeln!(
+            out,
+            "pages with issues: {}",
+            severities
+                .iter()
+                .map(|(severity, pages)| format!("{}={pages}", severity.label()))
+                .collect::<Vec<_>>()
+                .join(" ")
+        )?;
+    }
+    if !series.is_empty() || !state.facet_caps.is_empty() {
+        writeln!(
+            out,
+            "pagination: sequences={} with_problems={} capped_facet_paths={}",
+            series.len(),
+            series.iter().filter(|entry| entry.status() != "ok").count(),
+            state.facet_caps.len()
+        )?;
+    }
+    for trap in &state.crawl_traps {
+        writeln!(
+            out,
+            "crawl trap ({}): {} skipped={}",
+            trap.reason.label(),
+            trap.pattern,
+            trap.skipped
+        )?;
+    }
+    Ok(())
+}
+
+/// One `--progress-format json` line. `Finished` is written by `run_headless`
+/// with the crawl summary once the output is finalized.
+fn progress_event_json(event: &CrawlEvent) -> Option<Value> {
+    let mut value = match event {
+        CrawlEvent::Page {
+            row,
+            discovered_links,
+        } => json!({
+            "event": "page",
+            "url": row.url,
+            "status": row.status,
+            "retrieval_status": row.retrieval_status,
+            "indexability": row.indexability,
+            "title": row.title,
+            "seo_score": row.seo_score,
+            "response_time_ms": row.response_time,
+            "size": row.size,
+            "links": discovered_links.len(),
+            "issues": row.issues.iter().map(|issue| issue.label()).collect::<Vec<_>>(),
+            "highest_severity": row.highest_severity().map(IssueSeverity::label),
+        }),
+        CrawlEvent::Unretrieved { url, reason } => json!({
+            "event": "unretrieved",
+            "url": url,
+            "reason": reason,
+        }),
+        CrawlEvent::Stats { discovered } => json!({
+            "event": "stats",
+            "crawler_discovered": discovered,
+        }),
+        CrawlEvent::Finished => return None,
+        CrawlEvent::Status(message) => json!({ "event": "status", "message": message }),
+        CrawlEvent::Error(message) => json!({ "event": "error", "message": message }),
+        CrawlEvent::HostRates(rates) => json!({
+            "event": "host_rates",
+            "hosts": rates
+                .iter()
+                .map(|rate| {
+                    json!({
+                        "host": rate.host,
+                        "requests_per_sec": rate.requests_per_sec,
+                        "interval_ms": rate.interval_ms,
+                        "backoff_secs": rate.backoff_secs,
+                        "throttled": rate.throttled,
+                        "retries": rate.retries,
+                    })
+                })
+                .collect::<Vec<_>>(),
+        }),
+        CrawlEvent::BudgetExhausted(stop) => json!({
+            "event": "budget_exhausted",
+            "reason": stop.reason,
+            "pages": stop.pages,
+            "bytes": stop.bytes,
+            "elapsed_secs": stop.elapsed_secs,
+            "frontier": stop.frontier.len(),
+        }),
+        CrawlEvent::UrlVariants(variants) => json!({
+            "event": "url_variants",
+            "pages": variants.len(),
+            "variants": variants.iter().map(|(_, forms)| forms.len()).sum::<usize>(),
+        }),
+        CrawlEvent::FacetCaps(caps) => json!({
+            "event": "facet_caps",
+            "paths": caps.len(),
+            "skipped": caps.iter().map(|cap| cap.dropped).sum::<usize>(),
+        }),
+        CrawlEvent::CrawlTraps(traps) => json!({
+            "event": "crawl_traps",
+            "traps": traps
+                .iter()
+                .map(|trap| {
+                    json!({
+                        "reason": trap.reason.label(),
+                        "pattern": trap.pattern,
+                        "skipped": trap.skipped,
+                    })
+                })
+                .collect::<Vec<_>>(),
+        }),
+    };
+    value["time"] = json!(Utc::now().to_rfc3339());
+    Some(value)
+}
+
+async fn run_redirect_verification(cli: &Cli, mapping_path: &str) -> io::Result<()> {
+    let invalid = |err: String| io::Error::new(io::ErrorKind::InvalidInput, err);
+    let mut http = HttpOptions::from_cli(cli).map_err(invalid)?;
+    let entries = load_url_list(mapping_path).map_err(invalid)?;
+    // Credentials go to the old site; add the new host with --allow-host.
+    http.extend_scope(entries.iter().map(|(url, _)| url.as_str()));
+    if entries
+        .iter()
+        .any(|(_, expectation)| expectation.redirect_target.is_none())
+    {
+        return Err(invalid(format!(
+            "{mapping_path}: every row needs an expected target (new_url / expected_redirect column)"
+        )));
+    }
+
+    eprintln!("verifying {} redirects from {mapping_path}", entries.len());
+    let checks = verify_redirect_mapping(entries, cli.fetch_concurrency, &http)
+        .await
+        .map_err(io::Error::other)?;
+
+    let mut counts = HashMap::<RedirectOutcome, usize>::new();
+    let mut failed = 0usize;
+    for check in &checks {
+        *counts.entry(check.outcome).or_insert(0) += 1;
+        if check.outcome == RedirectOutcome::Match {
+            continue;
+        }
+        let verdict = if check.outcome.is_failure(cli.redirects_strict) {
+            failed += 1;
+            "FAIL"
+        } else {
+            "WARN"
+        };
+        let status = check
+            .final_status
+            .map(|s| s.to_string())
+            .unwrap_or_else(|| "-".to_string());
+        println!(
+            "{verdict} {:<17} {} -> {} [{status}, {} hops] expected {}",
+            check.outcome.label(),
+            check.old_url,
+            check.final_url,
+            check.hops.len(),
+            check.expected_url
+        );
+    }
+
+    if let Some(output) = cli.output.as_deref() {
+        write_redirect_report(output, &checks)?;
+        eprintln!("redirect report written to {output}");
+    }
+
+    let breakdown = RedirectOutcome::ALL
+        .iter()
+        .filter_map(|outcome| {
+            counts
+                .get(outcome)
+                .map(|count| format!("{}={count}", outcome.label()))
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+    println!(
+        "redirect verification {}: checked={} failed={} {breakdown}",
+        if failed == 0 { "PASSED" } else { "FAILED" },
+        checks.len(),
+        failed
     );
+    if failed > 0 {
+        return Err(io::Error::other(format!(
+            "redirect verification failed for {failed} of {} URLs",
+            checks.len()
+        )));
+    }
     Ok(())
 }
 
 fn run_review_headless(
     review_path: &str,
+    mut state: AppState,
     rx: &mut UnboundedReceiver<CrawlEvent>,
 ) -> io::Result<()> {
-    let mut state = AppState::default();
     loop {
         while let Ok(event) = rx.try_recv() {
             match &event {
@@ -168,7 +607,7 @@ fn run_review_headless(
 fn run_tui(
     session_label: &str,
     control_tx: Option<UnboundedSender<CrawlControl>>,
-    initial_fetch_concurrency: usize,
+    state: SharedState,
     output_target: Option<(&str, DataFormat)>,
     auto_close: bool,
     rx: &mut UnboundedReceiver<CrawlEvent>,
@@ -184,7 +623,7 @@ fn run_tui(
         &mut terminal,
         session_label,
         control_tx,
-        initial_fetch_concurrency,
+        state,
         output_target,
         auto_close,
         rx,
@@ -200,3 +639,124 @@ fn run_tui(
 
     tui_result
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn headless_run_waits_for_api_retries() {
+        let dir = std::env::temp_dir().join(format!("gh0st-headless-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let output = dir.join("crawl.csv").to_string_lossy().to_string();
+        let state = SharedState::default();
+        // A retry and two batches of added URLs; the crawl took up the first
+        // batch, the second one runs on its own after the retry.
+        lock_state(&state).queued_retries = 3;
+        lock_state(&state).frontier.merge_added();
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        tx.send(CrawlEvent::Finished).unwrap();
+        let crawler = std::thread::spawn(move || {
+            for url in ["https://example.com/retried", "https://example.com/added"] {
+                std::thread::sleep(Duration::from_millis(300));
+                tx.send(CrawlEvent::Unretrieved {
+                    url: url.to_string(),
+                    reason: "timeout".to_string(),
+                })
+                .unwrap();
+                tx.send(CrawlEvent::Finished).unwrap();
+            }
+            tx
+        });
+
+        let mut progress = Vec::new();
+        run_headless(
+            &output,
+            DataFormat::Csv,
+            state.clone(),
+            ProgressFormatArg::Text,
+            &mut progress,
+            &mut rx,
+        )
+        .unwrap();
+        let _tx = crawler.join().unwrap();
+        let exported = fs::read_to_string(&output).unwrap();
+        let _ = fs::remove_dir_all(&dir);
+
+        let state = lock_state(&state);
+        assert_eq!(state.finished_runs, 3);
+        assert!(state.closing);
+        assert!(exported.contains("https://example.com/retried"));
+        assert!(exported.contains("https://example.com/added"));
+    }
+
+    #[test]
+    fn headless_json_progress_writes_one_object_per_line() {
+        let dir = std::env::temp_dir().join(format!("gh0st-headless-json-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let output = dir.join("crawl.json").to_string_lossy().to_string();
+        let state = SharedState::default();
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let mut row = CrawlRow::not_retrieved("https://example.com/", "");
+        row.status = 200;
+        row.retrieval_status = "retrieved".to_string();
+        row.issues = vec![SeoIssue::MissingTitle];
+        for event in [
+            CrawlEvent::Status("Crawling \"example.com\"\nnow".to_string()),
+            CrawlEvent::Stats { discovered: 2 },
+            CrawlEvent::Page {
+                row: Box::new(row),
+                discovered_links: vec!["https://example.com/a".to_string()],
+            },
+            CrawlEvent::Unretrieved {
+                url: "https://example.com/a".to_string(),
+                reason: "timeout".to_string(),
+            },
+            CrawlEvent::Error("fetch failed".to_string()),
+            CrawlEvent::Finished,
+        ] {
+            tx.send(event).unwrap();
+        }
+
+        let mut progress = Vec::new();
+        run_headless(
+            &output,
+            DataFormat::Json,
+            state,
+            ProgressFormatArg::Json,
+            &mut progress,
+            &mut rx,
+        )
+        .unwrap();
+        let _ = fs::remove_dir_all(&dir);
+
+        let lines = String::from_utf8(progress)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str::<Value>(line).unwrap())
+            .collect::<Vec<_>>();
+        let events = lines
+            .iter()
+            .map(|line| line["event"].as_str().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            events,
+            [
+                "status",
+                "stats",
+                "page",
+                "unretrieved",
+                "error",
+                "finished"
+            ]
+        );
+        assert_eq!(lines[0]["message"], "Crawling \"example.com\"\nnow");
+        assert_eq!(lines[2]["url"], "https://example.com/");
+        assert_eq!(lines[2]["issues"], json!(["missing_title"]));
+        assert_eq!(lines[2]["highest_severity"], "error");
+        assert_eq!(lines[2]["links"], 1);
+        assert_eq!(lines[3]["parsed"], 2);
+        assert_eq!(lines[5]["parsed"], 2);
+        assert_eq!(lines[5]["output"], output);
+    }
+}
@@ -0,0 +1,851 @@
+use super::*;
+
+const API_MAX_BODY: usize = 1 << 20;
+const API_MAX_LINE: usize = 8 * 1024;
+const API_MAX_HEADERS: usize = 64;
+const API_MAX_ROWS: usize = 1000;
+/// Connections are served by a fixed set of workers; while all are busy a
+/// few more wait and the rest are closed.
+const API_WORKERS: usize = 4;
+const API_BACKLOG: usize = 16;
+
+const METRICS_CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Starts the `--api` server on its own threads and returns the bound
+/// address. Requests lock the shared UI state only while answering. Without
+/// a control channel (`--metrics`) only `GET /metrics` is served.
+pub(crate) fn start_control_api(
+    addr: &str,
+    state: SharedState,
+    control: Option<UnboundedSender<CrawlControl>>,
+) -> io::Result<std::net::SocketAddr> {
+    let what = if control.is_some() {
+        "control API"
+    } else {
+        "metrics"
+    };
+    let listener = TcpListener::bind(addr)
+        .map_err(|e| io::Error::new(e.kind(), format!("failed to bind {what} on {addr}: {e}")))?;
+    let bound = listener.local_addr()?;
+    let (queue, connections) = std::sync::mpsc::sync_channel::<TcpStream>(API_BACKLOG);
+    let connections = Arc::new(std::sync::Mutex::new(connections));
+    for _ in 0..API_WORKERS {
+        let connections = connections.clone();
+        let state = state.clone();
+        let control = control.clone();
+        std::thread::spawn(move || {
+            loop {
+                let next = connections.lock().unwrap_or_else(|e| e.into_inner()).recv();
+                let Ok(stream) = next else {
+                    break;
+                };
+                serve_api_connection(stream, &state, control.as_ref());
+            }
+        });
+    }
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            if let Err(std::sync::mpsc::TrySendError::Disconnected(_)) = queue.try_send(stream) {
+                break;
+            }
+        }
+    });
+    Ok(bound)
+}
+
+/// A status line warning that the control API is reachable from other
+/// machines, for a non-loopback `--api` address.
+pub(crate) fn exposed_api_warning(bound: std::net::SocketAddr) -> Option<String> {
+    (!bound.ip().is_loopback()).then(|| {
+        format!(
+            "Warning: the control API on {bound} has no authentication and is reachable from other machines"
+        )
+    })
+}
+
+#[derive(Debug)]
+struct ApiRequest {
+    method: String,
+    path: String,
+    query: HashMap<String, String>,
+    host: Option<String>,
+    content_type: Option<String>,
+    body: Value,
+}
+
+/// Only loopback names and the address the connection arrived on are
+/// accepted, so a web page cannot reach the API through a rebinding DNS
+/// name.
+fn api_host_allowed(host: &str, local: Option<std::net::IpAddr>) -> bool {
+    let host = host.trim();
+    let name = match host.strip_prefix('[') {
+        Some(rest) => match rest.split_once(']') {
+            Some((name, port)) if port.is_empty() || port.starts_with(':') => name,
+            _ => return false,
+        },
+        None => host.rsplit_once(':').map_or(host, |(name, _)| name),
+    };
+    if name.eq_ignore_ascii_case("localhost") {
+        return true;
+    }
+    name.parse::<std::net::IpAddr>()
+        .is_ok_and(|ip| ip.is_loopback() || Some(ip) == local)
+}
+
+fn serve_api_connection(
+    mut stream: TcpStream,
+    state: &SharedState,
+    control: Option<&UnboundedSender<CrawlControl>>,
+) {
+    let _ = stream.set_read_timeout(Some(Duration::from_secs(10)));
+    let _ = stream.set_write_timeout(Some(Duration::from_secs(10)));
+    let local = stream.local_addr().ok().map(|addr| addr.ip());
+    let json_response =
+        |(status, body): (u16, Value)| (status, "application/json", body.to_string());
+    let (status, content_type, body) = match read_api_request(&stream) {
+        // The metrics-only listener is read-only, so scrapers may address it
+        // by any name or through a port mapping.
+        Ok(request)
+            if control.is_some()
+                && !request
+                    .host
+                    .as_deref()
+                    .is_some_and(|host| api_host_allowed(host, local)) =>
+        {
+            json_response((403, json!({ "error": "Host header not allowed" })))
+        }
+        // A form or a simple cross-site request cannot send this type.
+        Ok(request)
+            if request.method == "POST"
+                && !request.content_type.as_deref().is_some_and(|value| {
+                    value
+                        .split(';')
+                        .next()
+                        .unwrap_or_default()
+                        .trim()
+                        .eq_ignore_ascii_case("application/json")
+                }) =>
+        {
+            json_response((
+                415,
+                json!({ "error": "POST requests need Content-Type: application/json" }),
+            ))
+        }
+        Ok(request) if request.path == "/metrics" => match request.method.as_str() {
+            "GET" => (
+                200,
+                METRICS_CONTENT_TYPE,
+                render_metrics(&lock_state(state)),
+            ),
+            _ => json_response((405, json!({ "error": "method not allowed" }))),
+        },
+        Ok(request) => match control {
+            Some(control) => json_response(route_api_request(&request, state, control)),
+            None => json_response((404, json!({ "error": "not found" }))),
+        },
+        Err(err) => json_response((400, json!({ "error": err }))),
+    };
+    let reason = match status {
+        200 => "OK",
+        202 => "Accepted",
+        400 => "Bad Request",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        409 => "Conflict",
+        415 => "Unsupported Media Type",
+        500 => "Internal Server Error",
+        _ => "Error",
+    };
+    let _ = write!(
+        stream,
+        "HTTP/1.1 {status} {reason}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    let _ = stream.flush();
+}
+
+fn read_api_request(stream: &TcpStream) -> Result<ApiRequest, String> {
+    use std::io::{BufRead, BufReader, Read};
+
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    let mut read_line = |line: &mut String| {
+        line.clear();
+        let read = (&mut reader)
+            .take(API_MAX_LINE as u64)
+            .read_line(line)
+            .map_err(|e| e.to_string())?;
+        if read == API_MAX_LINE && !line.ends_with('\n') {
+            return Err("request line or header too long".to_string());
+        }
+        Ok(read)
+    };
+    read_line(&mut line)?;
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return Err("malformed request line".to_string());
+    };
+    let method = method.to_ascii_uppercase();
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let path = path.trim_end_matches('/').to_string();
+    let query = url::form_urlencoded::parse(query.as_bytes())
+        .into_owned()
+        .collect::<HashMap<_, _>>();
+
+    let mut content_length = 0usize;
+    let mut host = None;
+    let mut content_type = None;
+    let mut headers = 0;
+    loop {
+        if read_line(&mut line)? == 0 {
+            break;
+        }
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        headers += 1;
+        if headers > API_MAX_HEADERS {
+            return Err("too many headers".to_string());
+        }
+        let Some((name, value)) = header.split_once(':') else {
+            continue;
+        };
+        let name = name.trim();
+        if name.eq_ignore_ascii_case("content-length") {
+            content_length = value
+                .trim()
+                .parse()
+                .map_err(|_| "invalid Content-Length".to_string())?;
+        } else if name.eq_ignore_ascii_case("host") {
+            host = Some(value.trim().to_string());
+        } else if name.eq_ignore_ascii_case("content-type") {
+            content_type = Some(value.trim().to_string());
+        }
+    }
+    if content_length > API_MAX_BODY {
+        return Err("request body too large".to_string());
+    }
+    let mut body = vec![0u8; content_length];
+    reader.read_exact(&mut body).map_err(|e| e.to_string())?;
+    let body = if body.iter().all(u8::is_ascii_whitespace) {
+        Value::Null
+    } else {
+        serde_json::from_slice(&body).map_err(|e| format!("invalid JSON body: {e}"))?
+    };
+    Ok(ApiRequest {
+        method,
+        path,
+        query,
+        host,
+        content_type,
+        body,
+    })
+}
+
+fn route_api_request(
+    request: &ApiRequest,
+    state: &SharedState,
+    control: &UnboundedSender<CrawlControl>,
+) -> (u16, Value) {
+    let closed = || (409, json!({ "error": "crawler control channel is closed" }));
+    let number = |name: &str, default: usize| {
+        request
+            .query
+            .get(name)
+            .and_then(|value| value.parse::<usize>().ok())
+            .unwrap_or(default)
+    };
+    match (request.method.as_str(), request.path.as_str()) {
+        ("GET", "") => (
+            200,
+            json!({
+                "endpoints": [
+                    "GET /status",
+                    "GET /rows?offset=&limit=&filter=",
+                    "GET /issues",
+                    "GET /queue?offset=&limit=",
+                    "GET /metrics",
+                    "POST /concurrency {\"value\": N}",
+                    "POST /retry {\"scope\": \"failed\"|\"complete\"} or {\"urls\": [...]}",
+                    "POST /seeds {\"urls\": [...]}",
+                    "POST /exclude {\"pattern\": \"*/tag/*\"}",
+                    "POST /pause",
+                    "POST /resume",
+                    "POST /shutdown",
+                ]
+            }),
+        ),
+        ("GET", "/status") => (200, api_status(&lock_state(state))),
+        ("GET", "/rows") => {
+            let offset = number("offset", 0);
+            let limit = number("limit", 100).clamp(1, API_MAX_ROWS);
+            let filter = request
+                .query
+                .get("filter")
+                .map(|filter| filter.trim().to_ascii_lowercase())
+                .unwrap_or_default();
+            let to_json = |row: &CrawlRow, links: &[String]| {
+                serde_json::to_value(row_to_export_record(row, links)).unwrap_or(Value::Null)
+            };
+            let mut total = 0;
+            let mut rows = Vec::new();
+            let mut collect = |row: &CrawlRow, links: &[String]| {
+                if filter.is_empty() || row_matches_filter_query(row, &filter) {
+                    if total >= offset && rows.len() < limit {
+                        rows.push(to_json(row, links));
+                    }
+                    total += 1;
+                }
+            };
+            let read_failed = |err: io::Error| {
+                (
+                    500,
+                    json!({ "error": format!("failed to read rows: {err}") }),
+                )
+            };
+            // Rows in memory are paged under the lock; spilled rows are read
+            // from the file after it is released.
+            let spill = {
+                let mut state = lock_state(state);
+                match state
+                    .rows
+                    .spill_snapshot(offset..offset.saturating_add(limit))
+                {
+                    Ok(Some(spill)) => Some(spill),
+                    Ok(None) => {
+                        state.rows.for_each_in_memory(&mut collect);
+                        None
+                    }
+                    Err(err) => return read_failed(err),
+                }
+            };
+            let scanned = match spill {
+                Some(spill) if filter.is_empty() => {
+                    total = spill.rows;
+                    spill.for_page(|row, links| rows.push(to_json(row, links)))
+                }
+                Some(spill) => spill.for_each(&mut collect),
+                None => Ok(()),
+            };
+            if let Err(err) = scanned {
+                return read_failed(err);
+            }
+            (
+                200,
+                json!({
+                    "total": total,
+                    "offset": offset,
+                    "limit": limit,
+                    "rows": rows,
+                }),
+            )
+        }
+        ("GET", "/issues") => {
+            let issues = lock_state(state)
+                .top_issues(usize::MAX)
+                .into_iter()
+                .map(|(issue, pages)| {
+                    json!({
+                        "issue": issue.label(),
+                        "severity": issue.severity().label(),
+                        "category": issue.category().label(),
+                        "penalty": issue.penalty(),
+                        "pages": pages,
+                    })
+                })
+                .collect::<Vec<_>>();
+            (200, Value::Array(issues))
+        }
+        ("GET", "/queue") => {
+            let offset = number("offset", 0);
+            let limit = number("limit", 100).clamp(1, API_MAX_ROWS);
+            let frontier = lock_state(state).frontier.clone();
+            let (total, urls) = frontier.page(offset, limit);
+            (
+                200,
+                json!({
+                    "total": total,
+                    "offset": offset,
+                    "limit": limit,
+                    "excluded": frontier.excluded(),
+                    "urls": urls,
+                }),
+            )
+        }
+        ("POST", "/seeds") => {
+            let mut state = lock_state(state);
+            if state.closing {
+                return (409, json!({ "error": "the crawl is finishing" }));
+            }
+            let Some(urls) = request.body.get("urls").and_then(Value::as_array) else {
+                return (400, json!({ "error": "expected {\"urls\": [...]}" }));
+            };
+            let urls = urls
+                .iter()
+                .filter_map(Value::as_str)
+                .map(ToString::to_string)
+                .collect::<Vec<_>>();
+            if urls.is_empty() {
+                return (400, json!({ "error": "expected {\"urls\": [...]}" }));
+            }
+            let submitted = urls.len();
+            if control.send(CrawlControl::AddUrls(urls)).is_err() {
+                return closed();
+            }
+            state.done = false;
+            state.queued_retries += 1;
+            (202, json!({ "submitted": submitted }))
+        }
+        ("POST", "/exclude") => {
+            let Some(pattern) = request
+                .body
+                .get("pattern")
+                .and_then(Value::as_str)
+                .map(str::trim)
+                .filter(|pattern| !pattern.is_empty())
+            else {
+                return (400, json!({ "error": "expected {\"pattern\": \"...\"}" }));
+            };
+            if control
+                .send(CrawlControl::ExcludePattern(pattern.to_string()))
+                .is_err()
+            {
+                return closed();
+            }
+            (202, json!({ "pattern": pattern }))
+        }
+        ("POST", "/concurrency") => {
+            let Some(value) = request.body.get("value").and_then(Value::as_u64) else {
+                return (400, json!({ "error": "expected {\"value\": N}" }));
+            };
+            let value = sanitize_fetch_concurrency(value as usize);
+            if control
+                .send(CrawlControl::SetFetchConcurrency(value))
+                .is_err()
+            {
+                return closed();
+            }
+            lock_state(state).fetch_concurrency = value;
+            (200, json!({ "fetch_concurrency": value }))
+        }
+        ("POST", "/retry") => {
+            let mut state = lock_state(state);
+            if state.closing {
+                return (409, json!({ "error": "the crawl is finishing" }));
+            }
+            let (scope, urls) = match request.body.get("urls").and_then(Value::as_array) {
+                Some(urls) => (
+                    RetryScope::SingleEntry,
+                    urls.iter()
+                        .filter_map(Value::as_str)
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>(),
+                ),
+                None => match request.body.get("scope").and_then(Value::as_str) {
+                    Some("failed") | None => (RetryScope::FailedOnly, state.retry_failed_urls()),
+                    Some("complete") => (RetryScope::Complete, state.retry_all_urls()),
+                    Some(other) => {
+                        return (400, json!({ "error": format!("unknown scope '{other}'") }));
+                    }
+                },
+            };
+            if urls.is_empty() {
+                return (400, json!({ "error": "no URLs available for retry" }));
+            }
+            let queued = urls.len();
+            if control
+                .send(CrawlControl::RetryUrls { scope, urls })
+                .is_err()
+            {
+                return closed();
+            }
+            state.done = false;
+            state.queued_retries += 1;
+            state.push_status(format!("API queued refresh: {}", scope.label()));
+            (200, json!({ "scope": scope.label(), "queued": queued }))
+        }
+        ("POST", "/pause") | ("POST", "/resume") => {
+            let pause = request.path == "/pause";
+            let command = if pause {
+                CrawlControl::Pause
+            } else {
+                CrawlControl::Resume
+            };
+            if control.send(command).is_err() {
+                return closed();
+            }
+            lock_state(state).paused = pause;
+            (200, json!({ "paused": pause }))
+        }
+        ("POST", "/shutdown") => {
+            if control.send(CrawlControl::Shutdown).is_err() {
+                return closed();
+            }
+            lock_state(state).push_status("Shutdown requested through the API".to_string());
+            (200, json!({ "shutdown": true }))
+        }
+        (
+            _,
+            "" | "/status" | "/rows" | "/issues" | "/queue" | "/seeds" | "/exclude"
+            | "/concurrency" | "/retry" | "/pause" | "/resume" | "/shutdown",
+        ) => (405, json!({ "error": "method not allowed" })),
+        _ => (404, json!({ "error": "not found" })),
+    }
+}
+
+fn api_status(state: &AppState) -> Value {
+    let buckets = status_buckets(&state.status_counts);
+    let discovered = state.discovered_total();
+    json!({
+        "state": if state.done {
+            "finished"
+        } else if state.paused {
+            "paused"
+        } else {
+            "running"
+        },
+        "parsed": state.parsed,
+        "discovered": discovered,
+        "remaining": discovered.saturating_sub(state.parsed),
+        "average_seo_score": state.average_seo_score(),
+        "fetch_concurrency": state.fetch_concurrency,
+        "paused": state.paused,
+        "status_buckets": {
+            "2xx": buckets.c2,
+            "3xx": buckets.c3,
+            "4xx": buckets.c4,
+            "5xx": buckets.c5,
+            "failed": buckets.c0,
+        },
+        "budget_stop": state.budget_stop,
+        "recent_status": state.status_messages,
+        "recent_errors": state.errors,
+    })
+}
+
+/// Prometheus text exposition of the crawl counters for `GET /metrics`.
+fn render_metrics(state: &AppState) -> String {
+    fn family(out: &mut String, name: &str, kind: &str, help: &str) {
+        out.push_str(&format!(
+            "# HELP gh0st_{name} {help}\n# TYPE gh0st_{name} {kind}\n"
+        ));
+    }
+    fn label(value: &str) -> String {
+        value
+            .replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace('\n', "\\n")
+    }
+
+    let mut out = String::new();
+    let discovered = state.discovered_total();
+    for (name, kind, help, value) in [
+        (
+            "pages_parsed_total",
+            "counter",
+            "Pages parsed so far.",
+            state.parsed,
+        ),
+        (
+            "urls_discovered",
+            "gauge",
+            "Unique URLs discovered so far.",
+            discovered,
+        ),
+        (
+            "queue_length",
+            "gauge",
+            "Discovered URLs not parsed yet.",
+            discovered.saturating_sub(state.parsed),
+        ),
+        (
+            "fetch_concurrency",
+            "gauge",
+            "Current fetch concurrency.",
+            state.fetch_concurrency,
+        ),
+        (
+            "paused",
+            "gauge",
+            "1 while the crawl is paused.",
+            usize::from(state.paused),
+        ),
+        (
+            "finished",
+            "gauge",
+            "1 once the crawl has finished.",
+            usize::from(state.done),
+        ),
+    ] {
+        family(&mut out, name, kind, help);
+        out.push_str(&format!("gh0st_{name} {value}\n"));
+    }
+
+    let buckets = status_buckets(&state.status_counts);
+    family(
+        &mut out,
+        "pages_by_status",
+        "gauge",
+        "Parsed pages by HTTP status class.",
+    );
+    for (class, count) in [
+        ("2xx", buckets.c2),
+        ("3xx", buckets.c3),
+        ("4xx", buckets.c4),
+        ("5xx", buckets.c5),
+        ("failed", buckets.c0),
+    ] {
+        out.push_str(&format!(
+            "gh0st_pages_by_status{{class=\"{class}\"}} {count}\n"
+        ));
+    }
+
+    family(
+        &mut out,
+        "issue_pages",
+        "gauge",
+        "Pages affected by each issue.",
+    );
+    for (issue, pages) in state.top_issues(usize::MAX) {
+        out.push_str(&format!(
+            "gh0st_issue_pages{{issue=\"{}\",severity=\"{}\",category=\"{}\"}} {pages}\n",
+            label(issue.label()),
+            issue.severity().label(),
+            issue.category().label()
+        ));
+    }
+
+    let mut hosts = state.host_stats.iter().collect::<Vec<_>>();
+    hosts.sort_by(|a, b| a.0.cmp(b.0));
+    family(
+        &mut out,
+        "response_time_seconds",
+        "histogram",
+        "Response time of retrieved pages by host.",
+    );
+    for (host, stats) in &hosts {
+        let host = label(host);
+        let times = &stats.response_times;
+        for (count, bound) in times.buckets.iter().zip(RESPONSE_TIME_BUCKETS_MS) {
+            out.push_str(&format!(
+                "gh0st_response_time_seconds_bucket{{host=\"{host}\",le=\"{}\"}} {count}\n",
+                bound as f64 / 1000.0
+            ));
+        }
+        out.push_str(&format!(
+            "gh0st_response_time_seconds_bucket{{host=\"{host}\",le=\"+Inf\"}} {}\n",
+            times.count
+        ));
+        out.push_str(&format!(
+            "gh0st_response_time_seconds_sum{{host=\"{host}\"}} {}\n",
+            times.total_ms as f64 / 1000.0
+        ));
+        out.push_str(&format!(
+            "gh0st_response_time_seconds_count{{host=\"{host}\"}} {}\n",
+            times.count
+        ));
+    }
+
+    family(
+        &mut out,
+        "retries_total",
+        "counter",
+        "Requests repeated after a 5xx/429 response, by host.",
+    );
+    for rate in &state.host_rates {
+        out.push_str(&format!(
+            "gh0st_retries_total{{host=\"{}\"}} {}\n",
+            label(&rate.host),
+            rate.retries
+        ));
+    }
+    family(
+        &mut out,
+        "throttled_total",
+        "counter",
+        "429/503 responses by host.",
+    );
+    for rate in &state.host_rates {
+        out.push_str(&format!(
+            "gh0st_throttled_total{{host=\"{}\"}} {}\n",
+            label(&rate.host),
+            rate.throttled
+        ));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+    use std::net::{Ipv4Addr, TcpListener};
+
+    /// Writes `raw` to a fresh loopback connection and returns the server end.
+    fn connection(raw: Vec<u8>) -> TcpStream {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let addr = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            let mut client = TcpStream::connect(addr).unwrap();
+            let _ = client.write_all(&raw);
+            let _ = client.shutdown(std::net::Shutdown::Write);
+        });
+        listener.accept().unwrap().0
+    }
+
+    fn read(raw: impl Into<Vec<u8>>) -> Result<ApiRequest, String> {
+        read_api_request(&connection(raw.into()))
+    }
+
+    fn serve(raw: &str, control: Option<&UnboundedSender<CrawlControl>>) -> String {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let addr = listener.local_addr().unwrap();
+        let raw = raw.to_string();
+        let client = std::thread::spawn(move || {
+            let mut client = TcpStream::connect(addr).unwrap();
+            client.write_all(raw.as_bytes()).unwrap();
+            let mut response = String::new();
+            client.read_to_string(&mut response).unwrap();
+            response
+        });
+        let state: SharedState = Arc::default();
+        serve_api_connection(listener.accept().unwrap().0, &state, control);
+        client.join().unwrap()
+    }
+
+    #[test]
+    fn api_request_is_parsed() {
+        let request = read(
+            "POST /pause/?reason=a%20b HTTP/1.1\r\nHost: 127.0.0.1:8080\r\ncontent-type: application/json\r\nContent-Length: 11\r\n\r\n{\"x\": true}",
+        )
+        .unwrap();
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path, "/pause");
+        assert_eq!(request.query["reason"], "a b");
+        assert_eq!(request.host.as_deref(), Some("127.0.0.1:8080"));
+        assert_eq!(request.content_type.as_deref(), Some("application/json"));
+        assert_eq!(request.body, json!({ "x": true }));
+    }
+
+    #[test]
+    fn api_request_limits() {
+        let long_line = format!("GET /{} HTTP/1.1\r\n\r\n", "a".repeat(API_MAX_LINE));
+        assert!(read(long_line).unwrap_err().contains("too long"));
+
+        let long_header = format!(
+            "GET / HTTP/1.1\r\nX-Filler: {}\r\n\r\n",
+            "a".repeat(API_MAX_LINE)
+        );
+        assert!(read(long_header).unwrap_err().contains("too long"));
+
+        let many_headers = format!(
+            "GET / HTTP/1.1\r\n{}\r\n",
+            "X-Filler: 1\r\n".repeat(API_MAX_HEADERS + 1)
+        );
+        assert_eq!(read(many_headers).unwrap_err(), "too many headers");
+        let enough_headers = format!(
+            "GET / HTTP/1.1\r\n{}\r\n",
+            "X-Filler: 1\r\n".repeat(API_MAX_HEADERS)
+        );
+        assert!(read(enough_headers).is_ok());
+
+        let large_body = format!(
+            "POST / HTTP/1.1\r\nContent-Length: {}\r\n\r\n",
+            API_MAX_BODY + 1
+        );
+        assert_eq!(read(large_body).unwrap_err(), "request body too large");
+        assert_eq!(
+            read("POST / HTTP/1.1\r\nContent-Length: -1\r\n\r\n").unwrap_err(),
+            "invalid Content-Length"
+        );
+        assert!(read("POST / HTTP/1.1\r\nContent-Length: 5\r\n\r\n{").is_err());
+        assert!(
+            read("POST / HTTP/1.1\r\nContent-Length: 3\r\n\r\n{x}")
+                .unwrap_err()
+                .starts_with("invalid JSON body")
+        );
+        assert_eq!(read("\r\n").unwrap_err(), "malformed request line");
+    }
+
+    #[test]
+    fn api_host_allowlist() {
+        let local = Some("192.168.1.20".parse().unwrap());
+        for host in [
+            "localhost",
+            "LOCALHOST:9000",
+            "127.0.0.1",
+            "127.0.0.1:9000",
+            "[::1]",
+            "[::1]:9000",
+            "192.168.1.20:9000",
+            " 127.0.0.1 ",
+        ] {
+            assert!(api_host_allowed(host, local), "{host}");
+        }
+        for host in [
+            "example.com",
+            "localhost.example.com:9000",
+            "127.0.0.1.nip.io",
+            "192.168.1.21",
+            "[::1",
+            "[192.168.1.20]extra",
+            "",
+        ] {
+            assert!(!api_host_allowed(host, local), "{host}");
+        }
+        assert!(!api_host_allowed("192.168.1.20", None));
+    }
+
+    #[test]
+    fn api_post_needs_json_content_type() {
+        let post = |content_type: &str| {
+            serve(
+                &format!(
+                    "POST /pause HTTP/1.1\r\nHost: localhost\r\n{content_type}Content-Length: 0\r\n\r\n"
+                ),
+                None,
+            )
+        };
+        assert!(post("").starts_with("HTTP/1.1 415 "));
+        assert!(post("Content-Type: text/plain\r\n").starts_with("HTTP/1.1 415 "));
+        assert!(
+            post("Content-Type: application/x-www-form-urlencoded\r\n")
+                .starts_with("HTTP/1.1 415 ")
+        );
+        // Past the gate; without a crawl to control every route is unknown.
+        assert!(
+            post("Content-Type: Application/JSON; charset=utf-8\r\n").starts_with("HTTP/1.1 404 ")
+        );
+    }
+
+    #[test]
+    fn api_host_gate_spares_the_metrics_listener() {
+        let (control, _control_rx) = mpsc::unbounded_channel();
+        let api = |raw: &str| serve(raw, Some(&control));
+        assert!(
+            api("GET /metrics HTTP/1.1\r\nHost: evil.example\r\n\r\n").starts_with("HTTP/1.1 403 ")
+        );
+        assert!(api("GET /metrics HTTP/1.1\r\n\r\n").starts_with("HTTP/1.1 403 "));
+        assert!(
+            api("GET /metrics HTTP/1.1\r\nHost: 127.0.0.1\r\n\r\n").starts_with("HTTP/1.1 200 ")
+        );
+
+        assert!(
+            serve(
+                "GET /metrics HTTP/1.1\r\nHost: metrics.internal\r\n\r\n",
+                None
+            )
+            .starts_with("HTTP/1.1 200 ")
+        );
+        assert!(serve("GET /metrics HTTP/1.1\r\n\r\n", None).starts_with("HTTP/1.1 200 "));
+        assert!(
+            serve(
+                "GET /status HTTP/1.1\r\nHost: metrics.internal\r\n\r\n",
+                None
+            )
+            .starts_with("HTTP/1.1 404 ")
+        );
+    }
+}
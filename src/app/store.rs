@@ -0,0 +1,710 @@
+use super::*;
+
+/// Time a frame may spend reading rows to extend a filtered view.
+const VIEW_SCAN_BUDGET: Duration = Duration::from_millis(40);
+
+/// Referrers kept in memory per URL for the page details.
+const INCOMING_SAMPLE: usize = 5;
+
+/// Size of a record in the referrer file.
+const REFERRER_RECORD: usize = 16;
+
+/// Crawled rows, their outgoing links and the incoming links of every URL
+/// they link to. In memory by default; with `--state-dir` each row is
+/// appended to a file and only a `RowEntry` stays in memory, and referrer
+/// lists are chained through a second file so each known URL keeps a fixed
+/// size index entry. URL sets outside the store (the frontier, the URL
+/// policy) stay in memory. Views are kept sorted and extended as rows arrive
+/// instead of being rebuilt every frame.
+#[derive(Default)]
+pub(crate) struct RowStore {
+    entries: Vec<RowEntry>,
+    memory: Vec<(CrawlRow, Vec<String>)>,
+    spill: Option<SpillFile>,
+    urls: HashMap<String, KnownUrl>,
+    linked: usize,
+    referrers: Option<ReferrerFile>,
+    page_view: RowView,
+    issue_view: RowView,
+}
+
+/// The fields views sort and filter on without reading the row.
+pub(crate) struct RowEntry {
+    pub(crate) url: String,
+    pub(crate) status: u16,
+    pub(crate) seo_score: u8,
+    response_time: u128,
+    pub(crate) retrieved: bool,
+    pub(crate) issues: Box<[SeoIssue]>,
+    /// Byte range of the row in the spill file.
+    offset: u64,
+    len: usize,
+}
+
+struct SpillFile {
+    path: PathBuf,
+    writer: io::BufWriter<File>,
+    reader: File,
+    written: u64,
+}
+
+/// Incoming link records: the referring row's position and the offset + 1
+/// of the previous record for the same target, so a URL's referrers are
+/// read back from its last record.
+struct ReferrerFile {
+    path: PathBuf,
+    writer: io::BufWriter<File>,
+    reader: File,
+    written: u64,
+}
+
+/// A URL with a stored row, linked from one, or both.
+#[derive(Default)]
+struct KnownUrl {
+    stored: bool,
+    linked: bool,
+    incoming: IncomingLinks,
+}
+
+/// Positions of matching rows in view order, plus how many rows have been
+/// checked against the filter.
+#[derive(Default)]
+struct RowView {
+    key: Option<(RowFilter, RowOrder)>,
+    positions: Vec<usize>,
+    scanned: usize,
+}
+
+#[derive(Clone, PartialEq)]
+enum RowFilter {
+    Query(String),
+    Issue(Option<SeoIssue>),
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum RowOrder {
+    Pages(PageSortMode, SortDirection),
+    MostIssues,
+}
+
+/// Row positions of the pages linking to a URL. The first few by URL are
+/// kept for the page details; the rest stay in `all` in memory, or in the
+/// referrer file from record `last` when rows are spilled.
+#[derive(Default)]
+struct IncomingLinks {
+    count: usize,
+    sample: Vec<usize>,
+    all: Vec<usize>,
+    last: u64,
+}
+
+impl IncomingLinks {
+    fn add(
+        &mut self,
+        source: usize,
+        entries: &[RowEntry],
+        referrers: Option<&mut ReferrerFile>,
+    ) -> io::Result<()> {
+        match referrers {
+            Some(file) => self.last = file.append(source, self.last)?,
+            None => self.all.push(source),
+        }
+        self.count += 1;
+        let url = &entries[source].url;
+        let at = self
+            .sample
+            .partition_point(|known| entries[*known].url < *url);
+        if at < INCOMING_SAMPLE {
+            self.sample.insert(at, source);
+            self.sample.truncate(INCOMING_SAMPLE);
+        }
+        Ok(())
+    }
+
+    /// The first `limit` referrers by URL.
+    fn sources(
+        &self,
+        limit: usize,
+        entries: &[RowEntry],
+        referrers: Option<&mut ReferrerFile>,
+    ) -> io::Result<Vec<usize>> {
+        if limit <= self.sample.len() || self.count == self.sample.len() {
+            return Ok(self.sample.iter().take(limit).copied().collect());
+        }
+        let mut all = match referrers {
+            Some(file) => file.chain(self.last)?,
+            None => self.all.clone(),
+        };
+        let by_url = |a: &usize, b: &usize| entries[*a].url.cmp(&entries[*b].url);
+        if limit < all.len() {
+            all.select_nth_unstable_by(limit, by_url);
+            all.truncate(limit);
+        }
+        all.sort_by(by_url);
+        Ok(all)
+    }
+}
+
+impl SpillFile {
+    fn create(dir: &str) -> io::Result<Self> {
+        fs::create_dir_all(dir)?;
+        let path = Path::new(dir).join(format!("gh0st-{}.rows", std::process::id()));
+        let writer = io::BufWriter::new(File::create(&path)?);
+        let reader = File::open(&path)?;
+        Ok(Self {
+            path,
+            writer,
+            reader,
+            written: 0,
+        })
+    }
+
+    fn append(&mut self, row: &CrawlRow, links: &[String]) -> io::Result<(u64, usize)> {
+        let line = serde_json::to_vec(&row_to_export_record(row, links))?;
+        self.writer.write_all(&line)?;
+        self.writer.write_all(b"\n")?;
+        let offset = self.written;
+        self.written += line.len() as u64 + 1;
+        Ok((offset, line.len()))
+    }
+
+    fn read(&mut self, offset: u64, len: usize) -> io::Result<(CrawlRow, Vec<String>)> {
+        use std::io::{Read, Seek, SeekFrom};
+
+        self.writer.flush()?;
+        self.reader.seek(SeekFrom::Start(offset))?;
+        let mut line = vec![0; len];
+        self.reader.read_exact(&mut line)?;
+        let record: ExportRecord = serde_json::from_slice(&line)?;
+        Ok(export_record_to_row(record))
+    }
+}
+
+/// Rows flushed to the spill file when the snapshot was taken; rows stored
+/// later are not visited.
+pub(crate) struct SpillSnapshot {
+    path: PathBuf,
+    pub(crate) rows: usize,
+    page: Vec<(u64, usize)>,
+}
+
+impl SpillSnapshot {
+    /// Visits the rows of the snapshot's page.
+    pub(crate) fn for_page(&self, mut visit: impl FnMut(&CrawlRow, &[String])) -> io::Result<()> {
+        use std::io::{Read, Seek, SeekFrom};
+
+        let mut file = File::open(&self.path)?;
+        for &(offset, len) in &self.page {
+            file.seek(SeekFrom::Start(offset))?;
+            let mut line = vec![0; len];
+            file.read_exact(&mut line)?;
+            let (row, links) = export_record_to_row(serde_json::from_slice(&line)?);
+            visit(&row, &links);
+        }
+        Ok(())
+    }
+
+    /// Visits every row in crawl order, reading the file sequentially.
+    pub(crate) fn for_each(&self, mut visit: impl FnMut(&CrawlRow, &[String])) -> io::Result<()> {
+        use std::io::BufRead;
+
+        let reader = io::BufReader::new(File::open(&self.path)?);
+        for line in reader.lines().take(self.rows) {
+            let record: ExportRecord = serde_json::from_str(&line?)?;
+            let (row, links) = export_record_to_row(record);
+            visit(&row, &links);
+        }
+        Ok(())
+    }
+}
+
+impl Drop for SpillFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+impl ReferrerFile {
+    fn create(dir: &str) -> io::Result<Self> {
+        fs::create_dir_all(dir)?;
+        let path = Path::new(dir).join(format!("gh0st-{}.links", std::process::id()));
+        let writer = io::BufWriter::new(File::create(&path)?);
+        let reader = File::open(&path)?;
+        Ok(Self {
+            path,
+            writer,
+            reader,
+            written: 0,
+        })
+    }
+
+    /// Appends a referrer after the record at `previous` and returns the
+    /// new record's offset + 1.
+    fn append(&mut self, source: usize, previous: u64) -> io::Result<u64> {
+        self.writer.write_all(&(source as u64).to_le_bytes())?;
+        self.writer.write_all(&previous.to_le_bytes())?;
+        self.written += REFERRER_RECORD as u64;
+        Ok(self.written - REFERRER_RECORD as u64 + 1)
+    }
+
+    /// The referrers chained from `last`, most recent first.
+    fn chain(&mut self, mut last: u64) -> io::Result<Vec<usize>> {
+        use std::io::{Read, Seek, SeekFrom};
+
+        self.writer.flush()?;
+        let mut sources = Vec::new();
+        let mut record = [0u8; REFERRER_RECORD];
+        while last > 0 {
+            self.reader.seek(SeekFrom::Start(last - 1))?;
+            self.reader.read_exact(&mut record)?;
+            let (source, previous) = record.split_at(8);
+            sources.push(u64::from_le_bytes(source.try_into().unwrap_or_default()) as usize);
+            last = u64::from_le_bytes(previous.try_into().unwrap_or_default());
+        }
+        Ok(sources)
+    }
+}
+
+impl Drop for ReferrerFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+impl RowOrder {
+    fn compare(self, a: (usize, &RowEntry), b: (usize, &RowEntry)) -> std::cmp::Ordering {
+        let ((a_pos, a), (b_pos, b)) = (a, b);
+        let (ordering, direction) = match self {
+            RowOrder::Pages(PageSortMode::Latest, direction) => (a_pos.cmp(&b_pos), direction),
+            RowOrder::Pages(PageSortMode::Status, direction) => {
+                (a.status.cmp(&b.status).then(a.url.cmp(&b.url)), direction)
+            }
+            RowOrder::Pages(PageSortMode::LowestSeoScore, direction) => (
+                a.seo_score.cmp(&b.seo_score).then(a.url.cmp(&b.url)),
+                direction,
+            ),
+            RowOrder::Pages(PageSortMode::HighestResponseTime, direction) => (
+                a.response_time
+                    .cmp(&b.response_time)
+                    .then_with(|| a.url.cmp(&b.url)),
+                direction,
+            ),
+            RowOrder::MostIssues => (
+                b.issues
+                    .len()
+                    .cmp(&a.issues.len())
+                    .then(a.seo_score.cmp(&b.seo_score))
+                    .then_with(|| a.url.cmp(&b.url)),
+                SortDirection::Asc,
+            ),
+        };
+        if direction == SortDirection::Desc {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    }
+}
+
+impl RowStore {
+    pub(crate) fn spill_to(dir: &str) -> io::Result<Self> {
+        Ok(Self {
+            spill: Some(SpillFile::create(dir)?),
+            referrers: Some(ReferrerFile::create(dir)?),
+            ..Self::default()
+        })
+    }
+
+    pub(crate) fn entries(&self) -> &[RowEntry] {
+        &self.entries
+    }
+
+    /// Whether a row for this URL has been stored.
+    pub(crate) fn contains(&self, url: &str) -> bool {
+        self.urls.get(url).is_some_and(|known| known.stored)
+    }
+
+    /// How many URLs stored rows link to.
+    pub(crate) fn linked(&self) -> usize {
+        self.linked
+    }
+
+    /// Every URL with a stored row or linked from one.
+    pub(crate) fn known_urls(&self) -> impl Iterator<Item = &String> {
+        self.urls.keys()
+    }
+
+    /// Stores a row and returns its position.
+    pub(crate) fn push(&mut self, row: &CrawlRow, links: &[String]) -> io::Result<usize> {
+        self.urls.entry(row.url.clone()).or_default().stored = true;
+        let (offset, len) = match self.spill.as_mut() {
+            Some(spill) => spill.append(row, links)?,
+            None => {
+                self.memory.push((row.clone(), links.to_vec()));
+                (0, 0)
+            }
+        };
+        self.entries.push(RowEntry {
+            url: row.url.clone(),
+            status: row.status,
+            seo_score: row.seo_score,
+            response_time: row.response_time,
+            retrieved: row.retrieval_status == "retrieved",
+            issues: row.issues.clone().into_boxed_slice(),
+            offset,
+            len,
+        });
+        Ok(self.entries.len() - 1)
+    }
+
+    /// Records the deduplicated outgoing links of the row for `url`; `source`
+    /// is its position when the row was stored by this call.
+    pub(crate) fn add_links(
+        &mut self,
+        url: &str,
+        source: Option<usize>,
+        links: &[String],
+    ) -> io::Result<()> {
+        for link in links {
+            let known = self.urls.entry(link.clone()).or_default();
+            if !known.linked {
+                known.linked = true;
+                self.linked += 1;
+            }
+            if let Some(source) = source
+                && link != url
+            {
+                known
+                    .incoming
+                    .add(source, &self.entries, self.referrers.as_mut())?;
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn incoming_count(&self, url: &str) -> usize {
+        self.urls.get(url).map_or(0, |known| known.incoming.count)
+    }
+
+    /// The URLs of the first `limit` pages linking to `url`, by URL.
+    pub(crate) fn incoming_sources(&mut self, url: &str, limit: usize) -> io::Result<Vec<String>> {
+        let Some(known) = self.urls.get(url) else {
+            return Ok(Vec::new());
+        };
+        let sources = known
+            .incoming
+            .sources(limit, &self.entries, self.referrers.as_mut())?;
+        Ok(sources
+            .into_iter()
+            .map(|position| self.entries[position].url.clone())
+            .collect())
+    }
+
+    fn get(&mut self, position: usize) -> Option<(CrawlRow, Vec<String>)> {
+        match self.spill.as_mut() {
+            Some(spill) => {
+                let entry = self.entries.get(position)?;
+                spill.read(entry.offset, entry.len).ok()
+            }
+            None => self.memory.get(position).cloned(),
+        }
+    }
+
+    fn matches(&mut self, position: usize, query: &str) -> bool {
+        match self.spill.as_mut() {
+            Some(spill) => {
+                let entry = &self.entries[position];
+                spill
+                    .read(entry.offset, entry.len)
+                    .is_ok_and(|(row, _)| row_matches_filter_query(&row, query))
+            }
+            None => row_matches_filter_query(&self.memory[position].0, query),
+        }
+    }
+
+    /// Visits every row kept in memory in crawl order; spilled rows are read
+    /// through `spill_snapshot`.
+    pub(crate) fn for_each_in_memory(&self, mut visit: impl FnMut(&CrawlRow, &[String])) {
+        for (row, links) in &self.memory {
+            visit(row, links);
+        }
+    }
+
+    /// The spill file as it stands, for reading rows without holding the
+    /// state lock, with the byte ranges of the rows at `page`. `None` when
+    /// rows are kept in memory.
+    pub(crate) fn spill_snapshot(
+        &mut self,
+        page: std::ops::Range<usize>,
+    ) -> io::Result<Option<SpillSnapshot>> {
+        let Some(spill) = self.spill.as_mut() else {
+            return Ok(None);
+        };
+        spill.writer.flush()?;
+        let end = page.end.min(self.entries.len());
+        let page = self.entries[page.start.min(end)..end]
+            .iter()
+            .map(|entry| (entry.offset, entry.len))
+            .collect();
+        Ok(Some(SpillSnapshot {
+            path: spill.path.clone(),
+            rows: self.entries.len(),
+            page,
+        }))
+    }
+
+    /// Brings a view up to date with the rows stored since it was last used.
+    /// Filters that need the full row stop after `VIEW_SCAN_BUDGET` and
+    /// continue on the next call; returns whether every row was checked.
+    fn refresh(&mut self, view: &mut RowView, filter: RowFilter, order: RowOrder) -> bool {
+        let key = Some((filter, order));
+        if view.key != key {
+            *view = RowView {
+                key,
+                ..RowView::default()
+            };
+        }
+        let Some((filter, _)) = view.key.as_ref() else {
+            return true;
+        };
+        let deadline = Instant::now() + VIEW_SCAN_BUDGET;
+        let first_new = view.positions.len();
+        while view.scanned < self.entries.len() {
+            let position = view.scanned;
+            let keep = match filter {
+                RowFilter::Query(query) if !query.is_empty() => {
+                    if Instant::now() >= deadline {
+                        break;
+                    }
+                    self.matches(position, query)
+                }
+                RowFilter::Query(_) | RowFilter::Issue(None) => true,
+                RowFilter::Issue(Some(issue)) => self.entries[position].issues.contains(issue),
+            };
+            if keep {
+                view.positions.push(position);
+            }
+            view.scanned += 1;
+        }
+        if view.positions.len() > first_new {
+            // The sort finds the existing sorted run and merges the new
+            // positions into it rather than sorting from scratch.
+            let entries = &self.entries;
+            view.positions
+                .sort_by(|a, b| order.compare((*a, &entries[*a]), (*b, &entries[*b])));
+        }
+        view.scanned == self.entries.len()
+    }
+
+    fn view_rows(&mut self, positions: &[usize]) -> Vec<CrawlRow> {
+        positions
+            .iter()
+            .filter_map(|position| self.get(*position).map(|(row, _)| row))
+            .collect()
+    }
+
+    /// Updates the Pages view and returns how many rows match so far and
+    /// whether the filter has checked every row.
+    pub(crate) fn refresh_page_view(
+        &mut self,
+        filter: &str,
+        sort: PageSortMode,
+        direction: SortDirection,
+    ) -> (usize, bool) {
+        let mut view = std::mem::take(&mut self.page_view);
+        let complete = self.refresh(
+            &mut view,
+            RowFilter::Query(filter.trim().to_ascii_lowercase()),
+            RowOrder::Pages(sort, direction),
+        );
+        let total = view.positions.len();
+        self.page_view = view;
+        (total, complete)
+    }
+
+    /// Rows `start..start + count` of the Pages view.
+    pub(crate) fn page_view_rows(&mut self, start: usize, count: usize) -> Vec<CrawlRow> {
+        let end = start
+            .saturating_add(count)
+            .min(self.page_view.positions.len());
+        let positions = self.page_view.positions[start.min(end)..end].to_vec();
+        self.view_rows(&positions)
+    }
+
+    /// How many pages have an issue (all pages for `None`) and the first
+    /// `limit` of them, most issues first.
+    pub(crate) fn issue_view_rows(
+        &mut self,
+        issue: Option<SeoIssue>,
+        limit: usize,
+    ) -> (usize, Vec<CrawlRow>) {
+        let mut view = std::mem::take(&mut self.issue_view);
+        self.refresh(&mut view, RowFilter::Issue(issue), RowOrder::MostIssues);
+        let total = view.positions.len();
+        let positions = view
+            .positions
+            .iter()
+            .take(limit)
+            .copied()
+            .collect::<Vec<_>>();
+        self.issue_view = view;
+        (total, self.view_rows(&positions))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> String {
+        let dir = std::env::temp_dir().join(format!("gh0st-store-{}-{name}", std::process::id()));
+        dir.to_string_lossy().into_owned()
+    }
+
+    fn row(path: &str, status: u16, seo_score: u8) -> CrawlRow {
+        let mut row = CrawlRow::not_retrieved(format!("https://example.com{path}"), "test");
+        row.status = status;
+        row.seo_score = seo_score;
+        row
+    }
+
+    fn urls(rows: &[CrawlRow]) -> Vec<&str> {
+        rows.iter().map(|row| row.url.as_str()).collect()
+    }
+
+    #[test]
+    fn spill_file_round_trips_rows() {
+        let dir = temp_dir("spill");
+        let mut spill = SpillFile::create(&dir).unwrap();
+        let mut first = row("/a", 200, 90);
+        first.title = "A, \"quoted\"\ntitle".to_string();
+        first
+            .extra_fields
+            .insert("words".to_string(), "12".to_string());
+        first.issues.push(SeoIssue::custom_with(
+            "store_test_custom",
+            7,
+            IssueSeverity::Notice,
+            IssueCategory::Links,
+        ));
+        let second = row("/b", 404, 0);
+        let links = ["https://example.com/b".to_string()];
+        let at_first = spill.append(&first, &links).unwrap();
+        let at_second = spill.append(&second, &[]).unwrap();
+
+        let (read, read_links) = spill.read(at_second.0, at_second.1).unwrap();
+        assert_eq!(
+            (read.url.as_str(), read.status),
+            ("https://example.com/b", 404)
+        );
+        assert!(read_links.is_empty());
+        let (read, read_links) = spill.read(at_first.0, at_first.1).unwrap();
+        assert_eq!(read.title, first.title);
+        assert_eq!(read.issues, first.issues);
+        assert_eq!(read.extra_field("words"), Some("12"));
+        assert_eq!(read_links, links);
+
+        let path = spill.path.clone();
+        drop(spill);
+        assert!(!path.exists());
+        let _ = fs::remove_dir(&dir);
+    }
+
+    #[test]
+    fn views_extend_as_rows_arrive_and_reset_on_changes() {
+        let dir = temp_dir("views");
+        for mut store in [RowStore::default(), RowStore::spill_to(&dir).unwrap()] {
+            store.push(&row("/shop/b", 404, 40), &[]).unwrap();
+            store.push(&row("/blog", 200, 90), &[]).unwrap();
+            let latest = (PageSortMode::Latest, SortDirection::Desc);
+            assert_eq!(store.refresh_page_view("", latest.0, latest.1), (2, true));
+
+            store.push(&row("/shop/a", 500, 10), &[]).unwrap();
+            assert_eq!(store.refresh_page_view("", latest.0, latest.1), (3, true));
+            assert_eq!(
+                urls(&store.page_view_rows(0, 10)),
+                [
+                    "https://example.com/shop/a",
+                    "https://example.com/blog",
+                    "https://example.com/shop/b"
+                ]
+            );
+
+            let by_status = (PageSortMode::Status, SortDirection::Asc);
+            assert_eq!(
+                store.refresh_page_view("shop", by_status.0, by_status.1),
+                (2, true)
+            );
+            assert_eq!(
+                urls(&store.page_view_rows(0, 10)),
+                ["https://example.com/shop/b", "https://example.com/shop/a"]
+            );
+            store.push(&row("/shop/c", 301, 70), &[]).unwrap();
+            store.push(&row("/about", 200, 80), &[]).unwrap();
+            assert_eq!(
+                store.refresh_page_view("shop", by_status.0, by_status.1),
+                (3, true)
+            );
+            assert_eq!(
+                urls(&store.page_view_rows(1, 10)),
+                ["https://example.com/shop/b", "https://example.com/shop/a"]
+            );
+
+            let worst = (PageSortMode::LowestSeoScore, SortDirection::Asc);
+            assert_eq!(store.refresh_page_view("", worst.0, worst.1), (5, true));
+            assert_eq!(
+                urls(&store.page_view_rows(0, 2)),
+                ["https://example.com/shop/a", "https://example.com/shop/b"]
+            );
+        }
+        let _ = fs::remove_dir(&dir);
+    }
+
+    #[test]
+    fn incoming_links_keep_every_referrer_in_url_order() {
+        let dir = temp_dir("links");
+        let target = "https://example.com/t".to_string();
+        for mut store in [RowStore::default(), RowStore::spill_to(&dir).unwrap()] {
+            let position = store.push(&row("/t", 200, 90), &[]).unwrap();
+            store
+                .add_links(&target, Some(position), std::slice::from_ref(&target))
+                .unwrap();
+            for n in [7, 3, 0, 8, 5, 1, 6, 2, 4] {
+                let referrer = row(&format!("/r{n}"), 200, 90);
+                let position = store.push(&referrer, &[]).unwrap();
+                store
+                    .add_links(&referrer.url, Some(position), std::slice::from_ref(&target))
+                    .unwrap();
+            }
+            store
+                .add_links(
+                    "https://example.com/r0",
+                    None,
+                    std::slice::from_ref(&target),
+                )
+                .unwrap();
+
+            let referrers = |range: std::ops::Range<usize>| {
+                range
+                    .map(|n| format!("https://example.com/r{n}"))
+                    .collect::<Vec<_>>()
+            };
+            assert_eq!(store.incoming_count(&target), 9);
+            assert_eq!(store.incoming_sources(&target, 3).unwrap(), referrers(0..3));
+            assert_eq!(store.incoming_sources(&target, 7).unwrap(), referrers(0..7));
+            assert_eq!(
+                store.incoming_sources(&target, 100).unwrap(),
+                referrers(0..9)
+            );
+            assert_eq!(store.linked(), 1);
+            assert!(store.contains(&target));
+            assert!(
+                store
+                    .incoming_sources("https://example.com/x", 5)
+                    .unwrap()
+                    .is_empty()
+            );
+        }
+        let _ = fs::remove_dir(&dir);
+    }
+}
@@ -1,10 +1,12 @@
+use super::*;
+
 const PAGE_JUMP_STEP: usize = 10;
 
-fn draw_loop(
+pub(crate) fn draw_loop(
     terminal: &mut Terminal<CrosstermBackend<Stdout>>,
     session_label_input: &str,
     control_tx: Option<UnboundedSender<CrawlControl>>,
-    initial_fetch_concurrency: usize,
+    shared_state: SharedState,
     output_target: Option<(&str, DataFormat)>,
     auto_close: bool,
     rx: &mut UnboundedReceiver<CrawlEvent>,
@@ -15,7 +17,6 @@ fn draw_loop(
         None
     };
     let session_label = session_label_input.to_string();
-    let mut state = AppState::default();
     let mut last_tick = Instant::now();
     let tick_rate = Duration::from_millis(120);
     let mut active_panel = ActivePanel::Pages;
@@ -26,19 +27,21 @@ fn draw_loop(
     let mut help_mode = false;
     let mut retry_prompt_mode = false;
     let mut retry_scope_selection = RetryScope::FailedOnly;
-    let mut fetch_concurrency = sanitize_fetch_concurrency(initial_fetch_concurrency);
-    let mut paused = false;
+    let mut frontier_prompt: Option<FrontierAction> = None;
+    let mut frontier_input = String::new();
     let mut selected_page_idx = 0usize;
     let mut selected_issue_idx = 0usize;
     let mut selected_issue_page_idx = 0usize;
     let mut page_table_state = TableState::default();
     let mut issue_table_state = TableState::default();
     let mut issue_page_table_state = TableState::default();
-    let mut paused_events = VecDeque::<CrawlEvent>::new();
     let mut page_table_area: Option<Rect> = None;
     let mut issue_distribution_area: Option<Rect> = None;
     let mut issue_urls_area: Option<Rect> = None;
+    // Only the visible window of the Pages view is loaded each frame.
     let mut page_view_urls: Vec<String> = Vec::new();
+    let mut page_view_start = 0usize;
+    let mut page_view_total = 0usize;
     let mut issue_view_urls: Vec<String> = Vec::new();
     let mut pages_pane = PagesPane::Table;
     let mut issues_pane = IssuesPane::Distribution;
@@ -48,28 +51,10 @@ fn draw_loop(
     let mut last_issue_url_click: Option<(usize, Instant)> = None;
 
     loop {
-        if !paused {
-            for _ in 0..300 {
-                let Some(event) = paused_events.pop_front() else {
-                    break;
-                };
-                handle_crawl_event(&mut state, sink.as_mut(), event)?;
-            }
-        }
-
+        // Held while drawing, released while waiting for input so the
+        // control API is not blocked for a whole tick.
+        let mut state = lock_state(&shared_state);
         while let Ok(event) = rx.try_recv() {
-            if paused
-                && matches!(
-                    event,
-                    CrawlEvent::Page { .. } | CrawlEvent::Unretrieved { .. }
-                )
-            {
-                paused_events.push_back(event);
-                if paused_events.len() > 20_000 {
-                    paused_events.pop_front();
-                }
-                continue;
-            }
             handle_crawl_event(&mut state, sink.as_mut(), event)?;
         }
 
@@ -80,10 +65,12 @@ fn draw_loop(
             page_view_urls.clear();
             issue_view_urls.clear();
 
+            let host_breakdown = state.host_breakdown();
+            let multi_host = host_breakdown.len() > 1;
             let chunks = Layout::default()
                 .direction(Direction::Vertical)
                 .constraints([
-                    Constraint::Length(5),
+                    Constraint::Length(if multi_host { 6 } else { 5 }),
                     Constraint::Length(3),
                     Constraint::Min(12),
                     Constraint::Length(5),
@@ -92,12 +79,14 @@ fn draw_loop(
 
             let crawl_title = if state.done {
                 if auto_close {
-                    "gh0st Crawl- Finished (auto-closing)"
+                    "gh0st Crawl- Finished (auto-closing)".to_string()
+                } else if let Some(summary) = state.budget_stop.as_deref() {
+                    format!("gh0st Crawl - Stopped by budget: {summary} (press q to quit)")
                 } else {
-                    "gh0st Crawl - Finished (press q to quit)"
+                    "gh0st Crawl - Finished (press q to quit)".to_string()
                 }
             } else {
-                "gh0st Crawl - Running (press q to quit)"
+                "gh0st Crawl - Running (press q to quit)".to_string()
             };
 
             let discovered_total = state.discovered_total();
@@ -106,7 +95,7 @@ fn draw_loop(
             let top_codes = top_status_codes(&state.status_counts, 10);
             let metric_label = Style::default().fg(Color::Gray);
             let sep_style = Style::default().fg(Color::DarkGray);
-            let header_lines = vec![
+            let mut header_lines = vec![
                 Line::from(vec![
                     Span::styled("Parsed ", metric_label),
                     Span::styled(
@@ -126,7 +115,7 @@ fn draw_loop(
                     Span::styled("  |  ", sep_style),
                     Span::styled("Linked ", metric_label),
                     Span::styled(
-                        state.discovered_seen.len().to_string(),
+                        state.rows.linked().to_string(),
                         Style::default().fg(Color::Blue),
                     ),
                     Span::styled("  |  ", sep_style),
@@ -170,24 +159,51 @@ fn draw_loop(
                     ),
                     Span::styled(session_label.clone(), Style::default().fg(Color::White)),
                 ]),
-                Line::from(vec![
-                    Span::styled("Buckets  ", metric_label),
-                    Span::styled("2xx ", Style::default().fg(Color::Green)),
-                    Span::styled(buckets.c2.to_string(), Style::default().fg(Color::Green)),
-                    Span::styled("   3xx ", Style::default().fg(Color::Yellow)),
-                    Span::styled(buckets.c3.to_string(), Style::default().fg(Color::Yellow)),
-                    Span::styled("   4xx ", Style::default().fg(Color::Red)),
-                    Span::styled(buckets.c4.to_string(), Style::default().fg(Color::Red)),
-                    Span::styled("   5xx ", Style::default().fg(Color::Magenta)),
-                    Span::styled(buckets.c5.to_string(), Style::default().fg(Color::Magenta)),
-                    Span::styled("   not_retrieved ", Style::default().fg(Color::LightRed)),
-                    Span::styled(
-                        buckets.c0.to_string(),
-                        Style::default()
-                            .fg(Color::LightRed)
-                            .add_modifier(Modifier::BOLD),
-                    ),
-                ]),
+                Line::from({
+                    let mut spans = vec![
+                        Span::styled("Buckets  ", metric_label),
+                        Span::styled("2xx ", Style::default().fg(Color::Green)),
+                        Span::styled(buckets.c2.to_string(), Style::default().fg(Color::Green)),
+                        Span::styled("   3xx ", Style::default().fg(Color::Yellow)),
+                        Span::styled(buckets.c3.to_string(), Style::default().fg(Color::Yellow)),
+                        Span::styled("   4xx ", Style::default().fg(Color::Red)),
+                        Span::styled(buckets.c4.to_string(), Style::default().fg(Color::Red)),
+                        Span::styled("   5xx ", Style::default().fg(Color::Magenta)),
+                        Span::styled(buckets.c5.to_string(), Style::default().fg(Color::Magenta)),
+                        Span::styled("   not_retrieved ", Style::default().fg(Color::LightRed)),
+                        Span::styled(
+                            buckets.c0.to_string(),
+                            Style::default()
+                                .fg(Color::LightRed)
+                                .add_modifier(Modifier::BOLD),
+                        ),
+                        Span::styled("  |  ", sep_style),
+                        Span::styled("Rate ", metric_label),
+                        Span::styled(
+                            format!(
+                                "{:.1}/s",
+                                state
+                                    .host_rates
+                                    .iter()
+                                    .map(|rate| rate.requests_per_sec)
+                                    .sum::<f64>()
+                            ),
+                            Style::default().fg(Color::Cyan),
+                        ),
+                    ];
+                    for rate in state.host_rates.iter().filter(|rate| rate.backoff_secs > 0) {
+                        spans.push(Span::styled(
+                            format!(
+                                "  backoff {} {}s ({} throttled)",
+                                rate.host, rate.backoff_secs, rate.throttled
+                            ),
+                            Style::default()
+                                .fg(Color::Yellow)
+                                .add_modifier(Modifier::BOLD),
+                        ));
+                    }
+                    spans
+                }),
                 Line::from({
                     let mut spans = vec![Span::styled("Top codes  ", metric_label)];
                     if top_codes.is_empty() {
@@ -217,6 +233,49 @@ fn draw_loop(
                     spans
                 }),
             ];
+            if multi_host {
+                let mut spans = vec![Span::styled("Hosts  ", metric_label)];
+                for (idx, (host, stats)) in host_breakdown.iter().enumerate() {
+                    if idx > 0 {
+                        spans.push(Span::raw("  "));
+                    }
+                    let avg = if stats.scored == 0 {
+                        0
+                    } else {
+                        (stats.score_total / stats.scored as u64) as u8
+                    };
+                    spans.push(Span::styled(
+                        format!("{host} "),
+                        Style::default().fg(Color::White),
+                    ));
+                    spans.push(Span::styled(
+                        stats.pages.to_string(),
+                        Style::default().fg(Color::Cyan),
+                    ));
+                    spans.push(Span::styled(
+                        format!(" ({} err, SEO ", stats.errors),
+                        if stats.errors > 0 {
+                            Style::default().fg(Color::Red)
+                        } else {
+                            metric_label
+                        },
+                    ));
+                    spans.push(Span::styled(avg.to_string(), seo_score_style(avg)));
+                    if let Some(rate) = state.host_rates.iter().find(|rate| rate.host == *host) {
+                        let spacing = if rate.interval_ms > 0 {
+                            format!(" every {}ms", rate.interval_ms)
+                        } else {
+                            String::new()
+                        };
+                        spans.push(Span::styled(
+                            format!(", {:.1}/s{spacing}", rate.requests_per_sec),
+                            metric_label,
+                        ));
+                    }
+                    spans.push(Span::styled(")", metric_label));
+                }
+                header_lines.push(Line::from(spans));
+            }
 
             let header = Paragraph::new(header_lines)
                 .block(
@@ -239,7 +298,7 @@ fn draw_loop(
             };
             let controls = Layout::default()
                 .direction(Direction::Horizontal)
-                .constraints([Constraint::Length(24), Constraint::Min(20)])
+                .constraints([Constraint::Length(36), Constraint::Min(20)])
                 .split(chunks[1]);
 
             let hotkey_style = Style::default()
@@ -255,6 +314,18 @@ fn draw_loop(
                     Span::styled("I", hotkey_style),
                     Span::styled(" Issues", tab_label_style),
                 ]),
+                Line::from(vec![
+                    Span::styled("H", hotkey_style),
+                    Span::styled(" Headers", tab_label_style),
+                ]),
+                Line::from(vec![
+                    Span::styled("N", hotkey_style),
+                    Span::styled(" Pagination", tab_label_style),
+                ]),
+                Line::from(vec![
+                    Span::styled("U", hotkey_style),
+                    Span::styled(" Queue", tab_label_style),
+                ]),
             ])
             .select(active_panel.as_index())
             .block(Block::default().title("Panel").borders(Borders::ALL))
@@ -279,15 +350,10 @@ fn draw_loop(
                 )
                 .ratio(ratio.clamp(0.0, 1.0))
                 .label(format!(
-                    "{:.1}% | quality {} | {}{}",
+                    "{:.1}% | quality {} | {}",
                     ratio * 100.0,
                     state.average_seo_score(),
-                    if paused { "paused" } else { "live" },
-                    if paused_events.is_empty() {
-                        String::new()
-                    } else {
-                        format!(" (buffered {})", paused_events.len())
-                    }
+                    if state.paused { "paused" } else { "live" },
                 ));
             f.render_widget(gauge, controls[1]);
 
@@ -298,14 +364,26 @@ fn draw_loop(
                         .constraints([Constraint::Percentage(66), Constraint::Percentage(34)])
                         .split(chunks[2]);
 
-                    let page_rows = state.filtered_rows_sorted(&filter, sort_mode, sort_direction);
+                    let (total, complete) =
+                        state
+                            .rows
+                            .refresh_page_view(&filter, sort_mode, sort_direction);
+                    let visible = (panel_chunks[0].height.saturating_sub(3) as usize).max(1);
+                    page_view_total = total;
+                    selected_page_idx = selected_page_idx.min(total.saturating_sub(1));
+                    if selected_page_idx < page_view_start {
+                        page_view_start = selected_page_idx;
+                    } else if selected_page_idx >= page_view_start + visible {
+                        page_view_start = selected_page_idx + 1 - visible;
+                    }
+                    page_view_start = page_view_start.min(total.saturating_sub(visible));
+                    let page_rows = state.rows.page_view_rows(page_view_start, visible);
                     page_view_urls = page_rows.iter().map(|row| row.url.clone()).collect();
+                    *page_table_state.offset_mut() = 0;
                     if page_rows.is_empty() {
-                        selected_page_idx = 0;
                         page_table_state.select(None);
                     } else {
-                        selected_page_idx = selected_page_idx.min(page_rows.len() - 1);
-                        page_table_state.select(Some(selected_page_idx));
+                        page_table_state.select(Some(selected_page_idx - page_view_start));
                     }
 
                     let rows = page_rows.iter().enumerate().map(|(idx, r)| {
@@ -345,7 +423,11 @@ fn draw_loop(
                     )
                     .block(
                         Block::default()
-                            .title(format!("Pages ({})", page_rows.len()))
+                            .title(if complete {
+                                format!("Pages ({total})")
+                            } else {
+                                format!("Pages ({total}, filtering...)")
+                            })
                             .borders(Borders::ALL)
                             .border_style(if pages_pane == PagesPane::Table {
                                 Style::default().fg(Color::Cyan)
@@ -357,7 +439,9 @@ fn draw_loop(
                     page_table_area = Some(panel_chunks[0]);
                     f.render_stateful_widget(pages_table, panel_chunks[0], &mut page_table_state);
 
-                    let detail = if let Some(row) = page_rows.get(selected_page_idx) {
+                    let detail = if let Some(row) =
+                        page_rows.get(selected_page_idx - page_view_start)
+                    {
                         let issues = if row.issues.is_empty() {
                             "none".to_string()
                         } else {
@@ -407,6 +491,30 @@ fn draw_loop(
                                 "Structured data blocks: {}",
                                 row.structured_data_count
                             )),
+                            Line::from(format!(
+                                "HSTS: {} | Mixed content: {} | HTTP internal links: {}",
+                                if row.strict_transport_security.is_empty() {
+                                    "none"
+                                } else {
+                                    &row.strict_transport_security
+                                },
+                                row.mixed_content_count,
+                                row.insecure_link_count
+                            )),
+                            Line::from(format!(
+                                "Cache-Control: {} | ETag: {} | Encoding: {}",
+                                if row.cache_control.is_empty() {
+                                    "none"
+                                } else {
+                                    &row.cache_control
+                                },
+                                if row.etag.is_empty() { "none" } else { "yes" },
+                                if row.content_encoding.is_empty() {
+                                    "unknown"
+                                } else {
+                                    &row.content_encoding
+                                }
+                            )),
                             Line::from(format!("Issues: {}", issues)),
                         ]
                     } else {
@@ -434,11 +542,7 @@ fn draw_loop(
 
                     let mut issue_nav_entries: Vec<(String, usize, Option<SeoIssue>)> = vec![(
                         "Most Problematic Pages".to_string(),
-                        state
-                            .all_rows
-                            .iter()
-                            .filter(|row| !row.issues.is_empty())
-                            .count(),
+                        state.pages_with_issues(),
                         None,
                     )];
                     issue_nav_entries.extend(
@@ -452,64 +556,65 @@ fn draw_loop(
                     issue_table_state.select(Some(selected_issue_idx));
 
                     let rows = issue_nav_entries.iter().map(|(label, count, issue)| {
-                        let style = if issue.is_none() {
-                            Style::default()
-                                .fg(Color::Cyan)
-                                .add_modifier(Modifier::BOLD)
-                        } else {
-                            Style::default()
+                        let Some(issue) = issue else {
+                            return Row::new(vec![
+                                Cell::from(label.clone()).style(
+                                    Style::default()
+                                        .fg(Color::Cyan)
+                                        .add_modifier(Modifier::BOLD),
+                                ),
+                                Cell::from(""),
+                                Cell::from(""),
+                                Cell::from(count.to_string()),
+                            ]);
                         };
+                        let style = issue_severity_style(issue.severity());
                         Row::new(vec![
                             Cell::from(label.clone()).style(style),
+                            Cell::from(issue.severity().label()).style(style),
+                            Cell::from(issue.category().label()),
                             Cell::from(count.to_string()),
                         ])
                     });
-                    let issues_table =
-                        Table::new(rows, [Constraint::Min(22), Constraint::Length(10)])
-                            .header(
-                                Row::new(vec!["Issue", "Pages"])
-                                    .style(Style::default().add_modifier(Modifier::BOLD)),
-                            )
-                            .row_highlight_style(
-                                Style::default()
-                                    .bg(Color::DarkGray)
-                                    .add_modifier(Modifier::BOLD),
-                            )
-                            .block(
-                                Block::default()
-                                    .title("Issue Distribution")
-                                    .borders(Borders::ALL)
-                                    .border_style(if issues_pane == IssuesPane::Distribution {
-                                        Style::default().fg(Color::Cyan)
-                                    } else {
-                                        Style::default().fg(Color::DarkGray)
-                                    }),
-                            )
-                            .column_spacing(1);
+                    let issues_table = Table::new(
+                        rows,
+                        [
+                            Constraint::Min(22),
+                            Constraint::Length(8),
+                            Constraint::Length(13),
+                            Constraint::Length(8),
+                        ],
+                    )
+                    .header(
+                        Row::new(vec!["Issue", "Severity", "Category", "Pages"])
+                            .style(Style::default().add_modifier(Modifier::BOLD)),
+                    )
+                    .row_highlight_style(
+                        Style::default()
+                            .bg(Color::DarkGray)
+                            .add_modifier(Modifier::BOLD),
+                    )
+                    .block(
+                        Block::default()
+                            .title("Issue Distribution")
+                            .borders(Borders::ALL)
+                            .border_style(if issues_pane == IssuesPane::Distribution {
+                                Style::default().fg(Color::Cyan)
+                            } else {
+                                Style::default().fg(Color::DarkGray)
+                            }),
+                    )
+                    .column_spacing(1);
                     issue_distribution_area = Some(panel_chunks[0]);
                     f.render_stateful_widget(issues_table, panel_chunks[0], &mut issue_table_state);
 
                     let selected_issue = issue_nav_entries
                         .get(selected_issue_idx)
-                        .and_then(|(_, _, issue)| *issue);
-                    let mut filtered_pages = state
-                        .all_rows
-                        .iter()
-                        .filter(|row| match selected_issue {
-                            Some(issue) => row.issues.contains(&issue),
-                            None => true,
-                        })
-                        .collect::<Vec<_>>();
-                    filtered_pages.sort_by(|a, b| {
-                        b.issues
-                            .len()
-                            .cmp(&a.issues.len())
-                            .then(a.seo_score.cmp(&b.seo_score))
-                            .then_with(|| a.url.cmp(&b.url))
-                    });
+                        .and_then(|(_, _, issue)| issue.clone());
+                    let (matching_pages, filtered_pages) =
+                        state.rows.issue_view_rows(selected_issue.clone(), 100);
                     issue_view_urls = filtered_pages
                         .iter()
-                        .take(100)
                         .map(|row| row.url.clone())
                         .collect::<Vec<_>>();
                     if issue_view_urls.is_empty() {
@@ -523,7 +628,6 @@ fn draw_loop(
 
                     let rows = filtered_pages
                         .iter()
-                        .take(100)
                         .enumerate()
                         .map(|(idx, row)| {
                             let url_style = if hovered_issue_url_idx == Some(idx) {
@@ -568,11 +672,9 @@ fn draw_loop(
                                 Some(issue) => format!(
                                     "Filtered Pages for '{}' ({})",
                                     issue.label(),
-                                    filtered_pages.len()
+                                    matching_pages
                                 ),
-                                None => {
-                                    format!("Most Problematic Pages ({})", filtered_pages.len())
-                                }
+                                None => format!("Most Problematic Pages ({matching_pages})"),
                             })
                             .borders(Borders::ALL)
                             .border_style(if issues_pane == IssuesPane::Urls {
@@ -585,6 +687,240 @@ fn draw_loop(
                     issue_urls_area = Some(panel_chunks[1]);
                     f.render_stateful_widget(table, panel_chunks[1], &mut issue_page_table_state);
                 }
+                ActivePanel::Headers => {
+                    let audited = state.header_audit_pages;
+                    let rows =
+                        state
+                            .header_summary()
+                            .into_iter()
+                            .map(|HeaderCoverage { name, present, most_common }| {
+                                let missing = audited.saturating_sub(present);
+                                let coverage = if audited == 0 {
+                                    0.0
+                                } else {
+                                    present as f64 * 100.0 / audited as f64
+                                };
+                                let coverage_style = if audited == 0 {
+                                    Style::default().fg(Color::DarkGray)
+                                } else if missing == 0 {
+                                    Style::default().fg(Color::Green)
+                                } else if present == 0 {
+                                    Style::default().fg(Color::Red)
+                                } else {
+                                    Style::default().fg(Color::Yellow)
+                                };
+                                let most_common = most_common
+                                    .map(|(value, count)| {
+                                        format!("{} ({count})", truncate_for_log(&value, 80))
+                                    })
+                                    .unwrap_or_else(|| "-".to_string());
+                                Row::new(vec![
+                                    Cell::from(name),
+                                    Cell::from(present.to_string()),
+                                    Cell::from(missing.to_string()),
+                                    Cell::from(format!("{coverage:.1}%")).style(coverage_style),
+                                    Cell::from(most_common),
+                                ])
+                            });
+                    let headers_table = Table::new(
+                        rows,
+                        [
+                            Constraint::Length(27),
+                            Constraint::Length(9),
+                            Constraint::Length(9),
+                            Constraint::Length(10),
+                            Constraint::Min(20),
+                        ],
+                    )
+                    .header(
+                        Row::new(vec![
+                            "Header",
+                            "Present",
+                            "Missing",
+                            "Coverage",
+                            "Most common",
+                        ])
+                        .style(Style::default().add_modifier(Modifier::BOLD)),
+                    )
+                    .block(
+                        Block::default()
+                            .title(format!("Response Headers ({audited} HTML pages audited)"))
+                            .borders(Borders::ALL)
+                            .border_style(Style::default().fg(Color::Cyan)),
+                    )
+                    .column_spacing(1);
+                    f.render_widget(headers_table, chunks[2]);
+                }
+                ActivePanel::Pagination => {
+                    let series = state.pagination_report();
+                    let capped = state.facet_caps.len() + state.crawl_traps.len();
+                    let facet_height = if capped == 0 {
+                        0
+                    } else {
+                        (capped as u16 + 3).min(12)
+                    };
+                    let pagination_chunks = Layout::default()
+                        .direction(Direction::Vertical)
+                        .constraints([Constraint::Min(5), Constraint::Length(facet_height)])
+                        .split(chunks[2]);
+                    let problems = series.iter().filter(|entry| entry.status() != "ok").count();
+                    let rows = series.iter().map(|entry| {
+                        let status_style = match entry.status() {
+                            "ok" => Style::default().fg(Color::Green),
+                            "inconsistent" => Style::default().fg(Color::Red),
+                            _ => Style::default().fg(Color::Yellow),
+                        };
+                        let missing = if entry.missing_pages.is_empty() {
+                            "-".to_string()
+                        } else {
+                            entry
+                                .missing_pages
+                                .iter()
+                                .take(8)
+                                .map(ToString::to_string)
+                                .collect::<Vec<_>>()
+                                .join(",")
+                        };
+                        Row::new(vec![
+                            Cell::from(truncate_for_log(&entry.key, 90)),
+                            Cell::from(entry.pages.to_string()),
+                            Cell::from(entry.last_page.to_string()),
+                            Cell::from(missing),
+                            Cell::from(entry.link_errors.to_string()),
+                            Cell::from(entry.canonical_to_first.to_string()),
+                            Cell::from(entry.status()).style(status_style),
+                        ])
+                    });
+                    let series_table = Table::new(
+                        rows,
+                        [
+                            Constraint::Min(30),
+                            Constraint::Length(6),
+                            Constraint::Length(6),
+                            Constraint::Length(18),
+                            Constraint::Length(10),
+                            Constraint::Length(10),
+                            Constraint::Length(18),
+                        ],
+                    )
+                    .header(
+                        Row::new(vec![
+                            "Series",
+                            "Pages",
+                            "Last",
+                            "Missing",
+                            "Link errs",
+                            "Canon->1",
+                            "Status",
+                        ])
+                        .style(Style::default().add_modifier(Modifier::BOLD)),
+                    )
+                    .block(
+                        Block::default()
+                            .title(format!(
+                                "Pagination ({} sequences, {problems} with problems)",
+                                series.len()
+                            ))
+                            .borders(Borders::ALL)
+                            .border_style(Style::default().fg(Color::Cyan)),
+                    )
+                    .column_spacing(1);
+                    f.render_widget(series_table, pagination_chunks[0]);
+
+                    if capped > 0 {
+                        let facet_rows = state.facet_caps.iter().map(|cap| {
+                            Row::new(vec![
+                                Cell::from("facet_cap"),
+                                Cell::from(truncate_for_log(&cap.path, 90)),
+                                Cell::from(cap.variants.to_string()),
+                                Cell::from(cap.dropped.to_string())
+                                    .style(Style::default().fg(Color::Yellow)),
+                            ])
+                        });
+                        let trap_rows = state.crawl_traps.iter().map(|trap| {
+                            Row::new(vec![
+                                Cell::from(trap.reason.label()),
+                                Cell::from(truncate_for_log(&trap.pattern, 90)),
+                                Cell::from("-"),
+                                Cell::from(trap.skipped.to_string())
+                                    .style(Style::default().fg(Color::Red)),
+                            ])
+                        });
+                        let facets_table = Table::new(
+                            facet_rows.chain(trap_rows),
+                            [
+                                Constraint::Length(18),
+                                Constraint::Min(30),
+                                Constraint::Length(9),
+                                Constraint::Length(9),
+                            ],
+                        )
+                        .header(
+                            Row::new(vec!["Reason", "Path / pattern", "Kept", "Skipped"])
+                                .style(Style::default().add_modifier(Modifier::BOLD)),
+                        )
+                        .block(
+                            Block::default()
+                                .title("Faceted Navigation Caps & Crawl Traps")
+                                .borders(Borders::ALL)
+                                .border_style(Style::default().fg(Color::Yellow)),
+                        )
+                        .column_spacing(1);
+                        f.render_widget(facets_table, pagination_chunks[1]);
+                    }
+                }
+                ActivePanel::Queue => {
+                    let excluded = state.frontier.excluded();
+                    let excluded_height = if excluded.is_empty() {
+                        0
+                    } else {
+                        (excluded.len() as u16 + 2).min(8)
+                    };
+                    let queue_chunks = Layout::default()
+                        .direction(Direction::Vertical)
+                        .constraints([Constraint::Min(5), Constraint::Length(excluded_height)])
+                        .split(chunks[2]);
+                    let visible = queue_chunks[0].height.saturating_sub(2) as usize;
+                    let (pending, urls) = state.frontier.page(0, visible);
+                    let mut lines = urls.into_iter().map(Line::from).collect::<Vec<_>>();
+                    if pending == 0 {
+                        lines.push(Line::styled(
+                            "No pending URLs",
+                            Style::default().fg(Color::DarkGray),
+                        ));
+                    } else if pending > visible && visible > 0 {
+                        lines.pop();
+                        lines.push(Line::styled(
+                            format!("... {} more", pending - visible + 1),
+                            Style::default().fg(Color::DarkGray),
+                        ));
+                    }
+                    f.render_widget(
+                        Paragraph::new(lines).block(
+                            Block::default()
+                                .title(format!(
+                                    "Queue ({} pending, {} excluded patterns) - a: add URLs, x: exclude",
+                                    pending,
+                                    excluded.len()
+                                ))
+                                .borders(Borders::ALL)
+                                .border_style(Style::default().fg(Color::Cyan)),
+                        ),
+                        queue_chunks[0],
+                    );
+                    if !excluded.is_empty() {
+                        let patterns = excluded.into_iter().map(Line::from).collect::<Vec<_>>();
+                        f.render_widget(
+                            Paragraph::new(patterns).block(
+                                Block::default()
+                                    .title("Excluded Patterns")
+                                    .borders(Borders::ALL)
+                                    .border_style(Style::default().fg(Color::Yellow)),
+                            ),
+                            queue_chunks[1],
+                        );
+                    }
+                }
             }
 
             let error_count = state.errors.len();
@@ -612,7 +948,7 @@ fn draw_loop(
             };
             let footer_status_style = if error_count > 0 {
                 Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)
-            } else if paused {
+            } else if state.paused {
                 Style::default()
                     .fg(Color::Yellow)
                     .add_modifier(Modifier::BOLD)
@@ -627,9 +963,9 @@ fn draw_loop(
             };
             let footer_border_style = if error_count > 0 {
                 Style::default().fg(Color::Red)
-            } else if help_mode || filter_mode || retry_prompt_mode {
+            } else if help_mode || filter_mode || retry_prompt_mode || frontier_prompt.is_some() {
                 Style::default().fg(Color::Yellow)
-            } else if paused {
+            } else if state.paused {
                 Style::default().fg(Color::Magenta)
             } else {
                 Style::default().fg(Color::DarkGray)
@@ -637,7 +973,7 @@ fn draw_loop(
 
             let state_label = if state.done {
                 "DONE"
-            } else if paused {
+            } else if state.paused {
                 "PAUSED"
             } else {
                 "LIVE"
@@ -648,12 +984,15 @@ fn draw_loop(
                 "HELP"
             } else if retry_prompt_mode {
                 "RETRY PROMPT"
+            } else if frontier_prompt.is_some() {
+                "FRONTIER INPUT"
             } else {
                 "NAVIGATION"
             };
             let pane_label = match active_panel {
                 ActivePanel::Pages => pages_pane.label(),
                 ActivePanel::Issues => issues_pane.label(),
+                ActivePanel::Headers | ActivePanel::Pagination | ActivePanel::Queue => "summary",
             };
             let footer_lines = vec![
                 Line::from(vec![
@@ -683,7 +1022,7 @@ fn draw_loop(
                     ),
                     Span::styled("   FETCH ", Style::default().fg(Color::DarkGray)),
                     Span::styled(
-                        fetch_concurrency.to_string(),
+                        state.fetch_concurrency.to_string(),
                         Style::default().fg(if control_tx.is_some() {
                             Color::LightCyan
                         } else {
@@ -703,15 +1042,6 @@ fn draw_loop(
                             Color::DarkGray
                         }),
                     ),
-                    Span::styled("   BUFFER ", Style::default().fg(Color::DarkGray)),
-                    Span::styled(
-                        paused_events.len().to_string(),
-                        Style::default().fg(if paused_events.is_empty() {
-                            Color::Green
-                        } else {
-                            Color::Yellow
-                        }),
-                    ),
                     Span::styled("   STATUS ", Style::default().fg(Color::DarkGray)),
                     Span::styled(
                         status_count.to_string(),
@@ -758,7 +1088,7 @@ fn draw_loop(
                     ),
                     Span::styled(" prev pane  ", Style::default().fg(Color::Gray)),
                     Span::styled(
-                        "P I",
+                        "P I H N U",
                         Style::default()
                             .fg(Color::White)
                             .add_modifier(Modifier::BOLD),
@@ -869,6 +1199,13 @@ fn draw_loop(
                             .add_modifier(Modifier::BOLD),
                     ),
                     Span::styled(" pause/resume  ", Style::default().fg(Color::Gray)),
+                    Span::styled(
+                        "a/x",
+                        Style::default()
+                            .fg(Color::White)
+                            .add_modifier(Modifier::BOLD),
+                    ),
+                    Span::styled(" add URL/exclude  ", Style::default().fg(Color::Gray)),
                     Span::styled(
                         "esc/enter",
                         Style::default()
@@ -903,7 +1240,7 @@ fn draw_loop(
                     .direction(Direction::Vertical)
                     .constraints([
                         Constraint::Length(8),
-                        Constraint::Length(7),
+                        Constraint::Length(8),
                         Constraint::Length(8),
                         Constraint::Min(3),
                     ])
@@ -915,7 +1252,7 @@ fn draw_loop(
                         Line::from("  pgup/pgdn: jump by 10 rows"),
                         Line::from("  g/G or home/end: first/last row"),
                         Line::from("  tab / shift+tab: switch pane focus"),
-                        Line::from("  p / i: switch panel"),
+                        Line::from("  p / i / h / n / u: switch panel"),
                         Line::from("  enter: open selected URL"),
                     ])
                     .block(Block::default().borders(Borders::ALL).title("Keys"))
@@ -928,8 +1265,9 @@ fn draw_loop(
                         Line::from("  r: cycle sort mode, d: sort direction"),
                         Line::from("  /: edit filter, ctrl+u: clear filter input"),
                         Line::from("  +/-: adjust fetch concurrency"),
-                        Line::from("  space: pause/resume live updates"),
+                        Line::from("  space: pause/resume crawling"),
                         Line::from("  t: retry selected URL, R: retry prompt"),
+                        Line::from("  a: add URLs to the queue, x: exclude a URL pattern"),
                     ])
                     .block(Block::default().borders(Borders::ALL).title("Actions"))
                     .wrap(Wrap { trim: true }),
@@ -941,6 +1279,7 @@ fn draw_loop(
                         Line::from("  Free text: home pricing"),
                         Line::from("  status:4xx  status:retrieved  status:404"),
                         Line::from("  issue:missing_h1  issue:none"),
+                        Line::from("  severity:error  category:security"),
                         Line::from("  host:example.com  title:blog  url:/pricing"),
                         Line::from("  Negate terms with ! or - (example: status:4xx -issue:noindex)"),
                     ])
@@ -1061,16 +1400,58 @@ fn draw_loop(
                     .wrap(Wrap { trim: true }),
                     prompt_chunks[2],
                 );
+            } else if let Some(action) = frontier_prompt {
+                let area = centered_rect(72, 24, f.area());
+                f.render_widget(Clear, area);
+                f.render_widget(
+                    Block::default()
+                        .title(action.title())
+                        .borders(Borders::ALL)
+                        .border_style(Style::default().fg(Color::Yellow)),
+                    area,
+                );
+                let prompt_chunks = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([Constraint::Length(3), Constraint::Min(3)])
+                    .split(area);
+                f.render_widget(
+                    Paragraph::new(if frontier_input.is_empty() {
+                        "<empty>"
+                    } else {
+                        &frontier_input
+                    })
+                    .block(Block::default().borders(Borders::ALL).title("Input"))
+                    .wrap(Wrap { trim: true }),
+                    prompt_chunks[0],
+                );
+                let hint = match action {
+                    FrontierAction::AddUrls => {
+                        "Space-separated URLs on the crawled hosts. Enter to queue, Esc to cancel."
+                    }
+                    FrontierAction::Exclude => {
+                        "Substring, or * wildcard matching the whole URL (example: */tag/*). \
+                         Enter to drop matching queued URLs, Esc to cancel."
+                    }
+                };
+                f.render_widget(
+                    Paragraph::new(hint)
+                        .block(Block::default().borders(Borders::NONE))
+                        .wrap(Wrap { trim: true }),
+                    prompt_chunks[1],
+                );
             }
         })?;
 
         if let Some(sink) = sink.as_mut() {
             sink.flush()?;
         }
+        drop(state);
 
         let timeout = tick_rate.saturating_sub(last_tick.elapsed());
         if event::poll(timeout)? {
-            match event::read()? {
+            let event = event::read()?;
+            let mut state = lock_state(&shared_state);
+            match event {
                 Event::Key(key) => {
                     if retry_prompt_mode {
                         match key.code {
@@ -1114,6 +1495,61 @@ fn draw_loop(
                             }
                             _ => {}
                         }
+                    } else if let Some(action) = frontier_prompt {
+                        match key.code {
+                            KeyCode::Esc => frontier_prompt = None,
+                            KeyCode::Enter => {
+                                frontier_prompt = None;
+                                let input = std::mem::take(&mut frontier_input);
+                                if let Some(control_tx) = control_tx.as_ref() {
+                                    let command = match action {
+                                        FrontierAction::AddUrls => {
+                                            let urls = input
+                                                .split_whitespace()
+                                                .map(str::to_string)
+                                                .collect::<Vec<_>>();
+                                            if urls.is_empty() {
+                                                None
+                                            } else {
+                                                state.done = false;
+                                                Some(CrawlControl::AddUrls(urls))
+                                            }
+                                        }
+                                        FrontierAction::Exclude => {
+                                            let pattern = input.trim().to_string();
+                                            if pattern.is_empty() {
+                                                None
+                                            } else {
+                                                Some(CrawlControl::ExcludePattern(pattern))
+                                            }
+                                        }
+                                    };
+                                    if let Some(command) = command
+                                        && control_tx.send(command).is_err()
+                                    {
+                                        state.push_error(
+                                            "crawler control channel is closed".to_string(),
+                                        );
+                                    }
+                                }
+                            }
+                            KeyCode::Backspace => {
+                                frontier_input.pop();
+                            }
+                            KeyCode::Char('u')
+                                if key.modifiers.contains(KeyModifiers::CONTROL) =>
+                            {
+                                frontier_input.clear();
+                            }
+                            KeyCode::Char(ch)
+                                if !key
+                                    .modifiers
+                                    .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) =>
+                            {
+                                frontier_input.push(ch);
+                            }
+                            _ => {}
+                        }
                     } else if help_mode {
                         match key.code {
                             KeyCode::Esc | KeyCode::Enter | KeyCode::Char('?') => {
@@ -1152,10 +1588,16 @@ fn draw_loop(
                             KeyCode::Tab => match active_panel {
                                 ActivePanel::Pages => pages_pane = pages_pane.cycle(),
                                 ActivePanel::Issues => issues_pane = issues_pane.cycle(),
+                                ActivePanel::Headers
+                                | ActivePanel::Pagination
+                                | ActivePanel::Queue => {}
                             },
                             KeyCode::BackTab => match active_panel {
                                 ActivePanel::Pages => pages_pane = pages_pane.reverse_cycle(),
                                 ActivePanel::Issues => issues_pane = issues_pane.reverse_cycle(),
+                                ActivePanel::Headers
+                                | ActivePanel::Pagination
+                                | ActivePanel::Queue => {}
                             },
                             KeyCode::Char('p') | KeyCode::Char('P') => {
                                 active_panel = ActivePanel::Pages
@@ -1163,6 +1605,26 @@ fn draw_loop(
                             KeyCode::Char('i') | KeyCode::Char('I') => {
                                 active_panel = ActivePanel::Issues
                             }
+                            KeyCode::Char('h') | KeyCode::Char('H') => {
+                                active_panel = ActivePanel::Headers
+                            }
+                            KeyCode::Char('n') | KeyCode::Char('N') => {
+                                active_panel = ActivePanel::Pagination
+                            }
+                            KeyCode::Char('u') | KeyCode::Char('U') => {
+                                active_panel = ActivePanel::Queue
+                            }
+                            KeyCode::Char('a') | KeyCode::Char('x') if control_tx.is_some() => {
+                                frontier_prompt = Some(if key.code == KeyCode::Char('a') {
+                                    FrontierAction::AddUrls
+                                } else {
+                                    FrontierAction::Exclude
+                                });
+                                frontier_input.clear();
+                                filter_mode = false;
+                                help_mode = false;
+                                retry_prompt_mode = false;
+                            }
                             KeyCode::Char('r') => sort_mode = sort_mode.cycle(),
                             KeyCode::Char('R') => {
                                 if control_tx.is_some() {
@@ -1174,12 +1636,9 @@ fn draw_loop(
                             KeyCode::Char('t') | KeyCode::Char('T') => {
                                 if let Some(control_tx) = control_tx.as_ref() {
                                     let selected_url = match active_panel {
-                                        ActivePanel::Pages => {
-                                            let selected = page_table_state
-                                                .selected()
-                                                .unwrap_or(selected_page_idx);
-                                            page_view_urls.get(selected).cloned()
-                                        }
+                                        ActivePanel::Pages => page_view_urls
+                                            .get(selected_page_idx.saturating_sub(page_view_start))
+                                            .cloned(),
                                         ActivePanel::Issues => {
                                             if issues_pane == IssuesPane::Urls {
                                                 let selected = issue_page_table_state
@@ -1190,6 +1649,9 @@ fn draw_loop(
                                                 None
                                             }
                                         }
+                                        ActivePanel::Headers
+                                        | ActivePanel::Pagination
+                                        | ActivePanel::Queue => None,
                                     };
 
                                     if let Some(url) = selected_url {
@@ -1231,14 +1693,15 @@ fn draw_loop(
                                 retry_prompt_mode = false;
                             }
                             KeyCode::Char('+') | KeyCode::Char('=') => {
-                                let next =
-                                    sanitize_fetch_concurrency(fetch_concurrency.saturating_add(1));
-                                if next != fetch_concurrency {
-                                    fetch_concurrency = next;
+                                let next = sanitize_fetch_concurrency(
+                                    state.fetch_concurrency.saturating_add(1),
+                                );
+                                if next != state.fetch_concurrency {
+                                    state.fetch_concurrency = next;
                                     if let Some(control_tx) = control_tx.as_ref() {
                                         if control_tx
                                             .send(CrawlControl::SetFetchConcurrency(
-                                                fetch_concurrency,
+                                                state.fetch_concurrency,
                                             ))
                                             .is_err()
                                         {
@@ -1250,14 +1713,15 @@ fn draw_loop(
                                 }
                             }
                             KeyCode::Char('-') | KeyCode::Char('_') => {
-                                let next =
-                                    sanitize_fetch_concurrency(fetch_concurrency.saturating_sub(1));
-                                if next != fetch_concurrency {
-                                    fetch_concurrency = next;
+                                let next = sanitize_fetch_concurrency(
+                                    state.fetch_concurrency.saturating_sub(1),
+                                );
+                                if next != state.fetch_concurrency {
+                                    state.fetch_concurrency = next;
                                     if let Some(control_tx) = control_tx.as_ref() {
                                         if control_tx
                                             .send(CrawlControl::SetFetchConcurrency(
-                                                fetch_concurrency,
+                                                state.fetch_concurrency,
                                             ))
                                             .is_err()
                                         {
@@ -1268,11 +1732,26 @@ fn draw_loop(
                                     }
                                 }
                             }
-                            KeyCode::Char(' ') => paused = !paused,
+                            KeyCode::Char(' ') if !state.done => {
+                                if let Some(control_tx) = control_tx.as_ref() {
+                                    let command = if state.paused {
+                                        CrawlControl::Resume
+                                    } else {
+                                        CrawlControl::Pause
+                                    };
+                                    if control_tx.send(command).is_ok() {
+                                        state.paused = !state.paused;
+                                    } else {
+                                        state.push_error(
+                                            "crawler control channel is closed".to_string(),
+                                        );
+                                    }
+                                }
+                            }
                             KeyCode::Enter => match active_panel {
                                 ActivePanel::Pages => {
                                     let selected =
-                                        page_table_state.selected().unwrap_or(selected_page_idx);
+                                        selected_page_idx.saturating_sub(page_view_start);
                                     if let Some(url) = page_view_urls.get(selected)
                                         && let Err(err) = open_url_in_browser(url)
                                     {
@@ -1295,6 +1774,9 @@ fn draw_loop(
                                         }
                                     }
                                 }
+                                ActivePanel::Headers
+                                | ActivePanel::Pagination
+                                | ActivePanel::Queue => {}
                             },
                             KeyCode::Up | KeyCode::Char('k')
                                 if key.modifiers == KeyModifiers::NONE =>
@@ -1314,6 +1796,9 @@ fn draw_loop(
                                                 selected_issue_page_idx.saturating_sub(1);
                                         }
                                     }
+                                    ActivePanel::Headers
+                                    | ActivePanel::Pagination
+                                    | ActivePanel::Queue => {}
                                 }
                             }
                             KeyCode::Down | KeyCode::Char('j')
@@ -1334,6 +1819,9 @@ fn draw_loop(
                                                 selected_issue_page_idx.saturating_add(1);
                                         }
                                     }
+                                    ActivePanel::Headers
+                                    | ActivePanel::Pagination
+                                    | ActivePanel::Queue => {}
                                 }
                             }
                             KeyCode::PageUp => match active_panel {
@@ -1353,6 +1841,9 @@ fn draw_loop(
                                                 .saturating_sub(PAGE_JUMP_STEP);
                                     }
                                 }
+                                ActivePanel::Headers
+                                | ActivePanel::Pagination
+                                | ActivePanel::Queue => {}
                             },
                             KeyCode::PageDown => match active_panel {
                                 ActivePanel::Pages => {
@@ -1371,6 +1862,9 @@ fn draw_loop(
                                                 .saturating_add(PAGE_JUMP_STEP);
                                     }
                                 }
+                                ActivePanel::Headers
+                                | ActivePanel::Pagination
+                                | ActivePanel::Queue => {}
                             },
                             KeyCode::Home | KeyCode::Char('g')
                                 if key.modifiers == KeyModifiers::NONE =>
@@ -1388,12 +1882,15 @@ fn draw_loop(
                                             selected_issue_page_idx = 0;
                                         }
                                     }
+                                    ActivePanel::Headers
+                                    | ActivePanel::Pagination
+                                    | ActivePanel::Queue => {}
                                 }
                             }
                             KeyCode::End | KeyCode::Char('G') => match active_panel {
                                 ActivePanel::Pages => {
                                     if pages_pane == PagesPane::Table {
-                                        selected_page_idx = page_view_urls.len().saturating_sub(1);
+                                        selected_page_idx = page_view_total.saturating_sub(1);
                                     }
                                 }
                                 ActivePanel::Issues => {
@@ -1405,13 +1902,16 @@ fn draw_loop(
                                             issue_view_urls.len().saturating_sub(1);
                                     }
                                 }
+                                ActivePanel::Headers
+                                | ActivePanel::Pagination
+                                | ActivePanel::Queue => {}
                             },
                             _ => {}
                         }
                     }
                 }
                 Event::Mouse(mouse) => {
-                    if retry_prompt_mode || help_mode || filter_mode {
+                    if retry_prompt_mode || help_mode || filter_mode || frontier_prompt.is_some() {
                         continue;
                     }
                     let modifier_held = mouse.modifiers.intersects(
@@ -1447,8 +1947,7 @@ fn draw_loop(
                                     && row_idx < page_view_urls.len()
                                     && point_in_rect(mouse.column, mouse.row, area)
                                 {
-                                    selected_page_idx = row_idx;
-                                    page_table_state.select(Some(selected_page_idx));
+                                    selected_page_idx = page_view_start + row_idx;
                                     let now = Instant::now();
                                     let double_click = last_page_click
                                         .map(|(prev_idx, prev_time)| {
@@ -1458,7 +1957,7 @@ fn draw_loop(
                                         })
                                         .unwrap_or(false);
                                     if (double_click || modifier_held)
-                                        && let Some(url) = page_view_urls.get(selected_page_idx)
+                                        && let Some(url) = page_view_urls.get(row_idx)
                                         && let Err(err) = open_url_in_browser(url)
                                     {
                                         state.push_error(format!(
@@ -1505,6 +2004,8 @@ fn draw_loop(
                                     last_issue_url_click = Some((selected_issue_page_idx, now));
                                 }
                             }
+                            ActivePanel::Headers | ActivePanel::Pagination | ActivePanel::Queue => {
+                            }
                         }
                     }
                 }
@@ -1516,7 +2017,7 @@ fn draw_loop(
             last_tick = Instant::now();
         }
 
-        if state.done && auto_close {
+        if auto_close && lock_state(&shared_state).done {
             break;
         }
     }
@@ -1,4 +1,6 @@
-fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
+use super::*;
+
+pub(crate) fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
     let popup_layout = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
@@ -18,13 +20,13 @@ fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
         .split(popup_layout[1])[1]
 }
 
-fn point_in_rect(x: u16, y: u16, rect: Rect) -> bool {
+pub(crate) fn point_in_rect(x: u16, y: u16, rect: Rect) -> bool {
     let right = rect.x.saturating_add(rect.width);
     let bottom = rect.y.saturating_add(rect.height);
     x >= rect.x && x < right && y >= rect.y && y < bottom
 }
 
-fn table_row_index_at(area: Rect, mouse_row: u16) -> Option<usize> {
+pub(crate) fn table_row_index_at(area: Rect, mouse_row: u16) -> Option<usize> {
     if area.height <= 3 {
         return None;
     }
@@ -37,7 +39,7 @@ fn table_row_index_at(area: Rect, mouse_row: u16) -> Option<usize> {
     }
 }
 
-fn open_url_in_browser(url: &str) -> Result<(), String> {
+pub(crate) fn open_url_in_browser(url: &str) -> Result<(), String> {
     if url.trim().is_empty() {
         return Err("empty URL".to_string());
     }
@@ -73,14 +75,14 @@ fn open_url_in_browser(url: &str) -> Result<(), String> {
     Ok(())
 }
 
-fn count_words(doc: &Html) -> usize {
+pub(crate) fn count_words(doc: &Html) -> usize {
     doc.root_element()
         .text()
         .flat_map(|t| t.split_whitespace())
         .count()
 }
 
-fn redirect_class(status: u16) -> &'static str {
+pub(crate) fn redirect_class(status: u16) -> &'static str {
     match status {
         301 | 308 => "Permanent",
         302 | 303 | 307 => "Temporary",
@@ -90,15 +92,15 @@ fn redirect_class(status: u16) -> &'static str {
 }
 
 #[derive(Default, Copy, Clone)]
-struct StatusBuckets {
-    c2: usize,
-    c3: usize,
-    c4: usize,
-    c5: usize,
-    c0: usize,
+pub(crate) struct StatusBuckets {
+    pub(crate) c2: usize,
+    pub(crate) c3: usize,
+    pub(crate) c4: usize,
+    pub(crate) c5: usize,
+    pub(crate) c0: usize,
 }
 
-fn status_buckets(counts: &HashMap<u16, usize>) -> StatusBuckets {
+pub(crate) fn status_buckets(counts: &HashMap<u16, usize>) -> StatusBuckets {
     let mut buckets = StatusBuckets::default();
     for (code, count) in counts {
         match *code {
@@ -113,7 +115,7 @@ fn status_buckets(counts: &HashMap<u16, usize>) -> StatusBuckets {
     buckets
 }
 
-fn top_status_codes(counts: &HashMap<u16, usize>, limit: usize) -> Vec<(u16, usize)> {
+pub(crate) fn top_status_codes(counts: &HashMap<u16, usize>, limit: usize) -> Vec<(u16, usize)> {
     let mut entries = counts
         .iter()
         .map(|(code, count)| (*code, *count))
@@ -122,7 +124,7 @@ fn top_status_codes(counts: &HashMap<u16, usize>, limit: usize) -> Vec<(u16, usi
     entries.into_iter().take(limit.max(1)).collect()
 }
 
-fn status_code_style(code: u16) -> Style {
+pub(crate) fn status_code_style(code: u16) -> Style {
     match code {
         0 => Style::default()
             .fg(Color::LightRed)
@@ -135,7 +137,16 @@ fn status_code_style(code: u16) -> Style {
     }
 }
 
-fn seo_score_style(score: u8) -> Style {
+pub(crate) fn issue_severity_style(severity: IssueSeverity) -> Style {
+    match severity {
+        IssueSeverity::Error => Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+        IssueSeverity::Warning => Style::default().fg(Color::Yellow),
+        IssueSeverity::Notice => Style::default().fg(Color::Cyan),
+        IssueSeverity::Info => Style::default().fg(Color::Gray),
+    }
+}
+
+pub(crate) fn seo_score_style(score: u8) -> Style {
     match score {
         85..=100 => Style::default()
             .fg(Color::Green)
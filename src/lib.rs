@@ -0,0 +1,17 @@
+//! gh0st's crawler and SEO analysis as a library.
+//!
+//! [`CrawlBuilder`] starts a crawl and streams [`CrawlEvent`]s; [`analyze_html`]
+//! audits a document fetched elsewhere; [`load_rows_from_file`] and
+//! [`OutputSink`] read and write the CSV/JSON export format. Page checks are
+//! [`Analyzer`]s held by the [`AnalyzerRegistry`]. [`run`] is the
+//! command-line entry point used by the `gh0st` binary.
+
+mod app;
+
+pub use app::{
+    Analyzer, AnalyzerOutput, AnalyzerRegistry, BudgetStop, CrawlBuilder, CrawlControl, CrawlEvent,
+    CrawlHandle, CrawlRow, CrawlTrap, CsvSink, CustomIssue, DataFormat, FacetCap, HostRate,
+    IssueCategory, IssueSeverity, JsonSink, OutputSink, PageContext, RetryScope, SeoIssue,
+    TrapReason, analyze_html, detect_data_format, load_rows_from_file, run,
+};
+pub use scraper::Html;
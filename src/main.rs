@@ -1,6 +1,12 @@
-mod app;
+use std::process::ExitCode;
 
 #[tokio::main]
-async fn main() -> std::io::Result<()> {
-    app::run().await
+async fn main() -> ExitCode {
+    match gh0st::run().await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::FAILURE
+        }
+    }
 }